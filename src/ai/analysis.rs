@@ -0,0 +1,40 @@
+use crate::{Move, SearchStats};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Outcome {
+    Win { plies: usize },
+    Draw,
+    Loss { plies: usize },
+    Unknown,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MoveScore {
+    pub player_move: Move,
+    pub score: i16,
+    pub outcome: Outcome,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Hint {
+    pub player_move: Move,
+    pub score: Option<i16>,
+    pub outcome: Outcome,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Analysis {
+    pub moves: Vec<MoveScore>,
+    pub best_move: Move,
+    pub score: i16,
+    pub outcome: Outcome,
+    pub principal_variation: Vec<Move>,
+    pub depth: usize,
+    pub stats: SearchStats,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SearchResult {
+    pub best_move: Move,
+    pub stats: SearchStats,
+}
@@ -0,0 +1,129 @@
+use crate::{CancelToken, SearchLimits};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Instant;
+
+#[derive(Debug)]
+pub(crate) struct Budget {
+    deadline: Option<Instant>,
+    max_nodes: Option<u64>,
+    nodes: AtomicU64,
+    exhausted: AtomicBool,
+    cancel: Option<CancelToken>,
+}
+
+impl Budget {
+    const CLOCK_INTERVAL: u64 = 256;
+
+    pub fn new(limits: &SearchLimits) -> Self {
+        Self {
+            deadline: limits.move_time.map(|duration| Instant::now() + duration),
+            max_nodes: limits.max_nodes,
+            nodes: AtomicU64::new(0),
+            exhausted: AtomicBool::new(false),
+            cancel: None,
+        }
+    }
+
+    pub fn with_cancel(limits: &SearchLimits, cancel: &CancelToken) -> Self {
+        Self {
+            cancel: Some(cancel.clone()),
+            ..Self::new(limits)
+        }
+    }
+
+    pub fn is_limited(&self) -> bool {
+        self.deadline.is_some() || self.max_nodes.is_some()
+    }
+
+    pub fn is_cancellable(&self) -> bool {
+        self.cancel.is_some()
+    }
+
+    pub fn tick(&self) -> bool {
+        let nodes = self.nodes.fetch_add(1, Ordering::Relaxed) + 1;
+        if self.max_nodes.is_some_and(|max_nodes| nodes > max_nodes) {
+            self.exhausted.store(true, Ordering::Relaxed);
+        }
+        if self.cancel.as_ref().is_some_and(CancelToken::is_cancelled) {
+            self.exhausted.store(true, Ordering::Relaxed);
+        }
+        if nodes & (Self::CLOCK_INTERVAL - 1) == 0 {
+            if let Some(deadline) = self.deadline {
+                if Instant::now() >= deadline {
+                    self.exhausted.store(true, Ordering::Relaxed);
+                }
+            }
+        }
+        !self.is_exhausted()
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        self.exhausted.load(Ordering::Relaxed)
+    }
+
+    pub fn nodes(&self) -> u64 {
+        self.nodes.load(Ordering::Relaxed)
+    }
+}
+
+impl Default for Budget {
+    fn default() -> Self {
+        Self::new(&SearchLimits::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn it_never_runs_out_without_limits() {
+        let budget = Budget::default();
+        assert!(!budget.is_limited());
+        for _ in 0..1_000 {
+            assert!(budget.tick());
+        }
+        assert_eq!(1_000, budget.nodes());
+    }
+
+    #[test]
+    fn it_runs_out_of_nodes() {
+        let budget = Budget::new(&SearchLimits {
+            max_nodes: Some(3),
+            ..SearchLimits::default()
+        });
+        assert!(budget.is_limited());
+        assert!(budget.tick());
+        assert!(budget.tick());
+        assert!(budget.tick());
+        assert!(!budget.tick());
+        assert!(budget.is_exhausted());
+    }
+
+    #[test]
+    fn it_runs_out_of_time() {
+        let budget = Budget::new(&SearchLimits {
+            move_time: Some(Duration::from_millis(0)),
+            ..SearchLimits::default()
+        });
+        assert!(budget.is_limited());
+        for _ in 1..Budget::CLOCK_INTERVAL {
+            assert!(budget.tick());
+        }
+        assert!(!budget.tick());
+        assert!(budget.is_exhausted());
+    }
+
+    #[test]
+    fn it_runs_out_when_cancelled() {
+        let cancel = CancelToken::new();
+        let budget = Budget::with_cancel(&SearchLimits::default(), &cancel);
+        assert!(!budget.is_limited());
+        assert!(budget.is_cancellable());
+        assert!(budget.tick());
+        cancel.cancel();
+        assert!(!budget.tick());
+        assert!(budget.is_exhausted());
+    }
+}
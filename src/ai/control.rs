@@ -0,0 +1,102 @@
+use crate::Move;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{MoveError, SearchResult};
+#[cfg(not(target_arch = "wasm32"))]
+use std::thread::{self, JoinHandle};
+
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Progress {
+    pub depth: usize,
+    pub nodes: u64,
+    pub best_move: Option<Move>,
+    pub elapsed: Duration,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug)]
+pub struct SearchHandle {
+    cancel: CancelToken,
+    thread: JoinHandle<Result<SearchResult, MoveError>>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl SearchHandle {
+    pub(crate) fn spawn<F>(cancel: CancelToken, search: F) -> Self
+    where
+        F: FnOnce(&CancelToken) -> Result<SearchResult, MoveError> + Send + 'static,
+    {
+        let token = cancel.clone();
+        Self {
+            cancel,
+            thread: thread::spawn(move || search(&token)),
+        }
+    }
+
+    pub fn cancel(&self) {
+        self.cancel.cancel();
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.thread.is_finished()
+    }
+
+    pub fn join(self) -> Result<SearchResult, MoveError> {
+        self.thread.join().expect("search thread panicked")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(not(target_arch = "wasm32"))]
+    use crate::SearchStats;
+
+    #[test]
+    fn it_shares_cancellation_between_clones() {
+        let token = CancelToken::new();
+        let clone = token.clone();
+        assert!(!token.is_cancelled());
+        clone.cancel();
+        assert!(token.is_cancelled());
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn it_joins_the_search_thread() {
+        let handle = SearchHandle::spawn(CancelToken::new(), |cancel| {
+            while !cancel.is_cancelled() {
+                thread::yield_now();
+            }
+            Ok(SearchResult {
+                best_move: Move::Place(4),
+                stats: SearchStats::default(),
+            })
+        });
+        handle.cancel();
+        let result = handle.join().expect("search succeeds");
+        assert_eq!(Move::Place(4), result.best_move);
+    }
+}
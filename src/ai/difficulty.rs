@@ -0,0 +1,76 @@
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+    Perfect,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Handicap {
+    pub max_depth: Option<usize>,
+    pub mistake_percent: u8,
+    pub margin: i16,
+}
+
+impl Handicap {
+    pub fn is_perfect(&self) -> bool {
+        self.max_depth.is_none() && self.mistake_percent == 0 && self.margin == 0
+    }
+}
+
+impl Default for Handicap {
+    fn default() -> Self {
+        Self::from(Difficulty::Perfect)
+    }
+}
+
+impl From<Difficulty> for Handicap {
+    fn from(difficulty: Difficulty) -> Self {
+        match difficulty {
+            Difficulty::Easy => Self {
+                max_depth: Some(1),
+                mistake_percent: 30,
+                margin: 4,
+            },
+            Difficulty::Medium => Self {
+                max_depth: Some(2),
+                mistake_percent: 15,
+                margin: 2,
+            },
+            Difficulty::Hard => Self {
+                max_depth: Some(4),
+                mistake_percent: 5,
+                margin: 1,
+            },
+            Difficulty::Perfect => Self {
+                max_depth: None,
+                mistake_percent: 0,
+                margin: 0,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_is_perfect_by_default() {
+        assert!(Handicap::default().is_perfect());
+        assert_eq!(Handicap::from(Difficulty::Perfect), Handicap::default());
+    }
+
+    #[test]
+    fn it_gets_weaker_with_lower_difficulty() {
+        let hard = Handicap::from(Difficulty::Hard);
+        let medium = Handicap::from(Difficulty::Medium);
+        let easy = Handicap::from(Difficulty::Easy);
+        assert!(!hard.is_perfect());
+        assert!(easy.max_depth < medium.max_depth && medium.max_depth < hard.max_depth);
+        assert!(easy.mistake_percent > medium.mistake_percent);
+        assert!(medium.mistake_percent > hard.mistake_percent);
+        assert!(easy.margin > medium.margin && medium.margin > hard.margin);
+    }
+}
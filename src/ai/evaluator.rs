@@ -0,0 +1,235 @@
+use crate::{Board, Mark};
+use core::convert::TryFrom;
+use core::fmt::{self, Debug, Display};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+pub trait Evaluator: Send + Sync {
+    fn evaluate(&self, board: &Board, mark: Mark) -> i16;
+}
+
+impl Debug for dyn Evaluator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Evaluator")
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Heuristic {
+    pub open_line: i16,
+    pub two: i16,
+    pub three: i16,
+    pub fork: i16,
+    pub presence: i16,
+}
+
+impl Heuristic {
+    const LIMIT: i32 = 500;
+    const NAMES: [&'static str; 5] = ["open_line", "two", "three", "fork", "presence"];
+
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        Self::parse(&text)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid heuristic weights"))
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    pub(crate) fn weights(&self) -> [i16; 5] {
+        [
+            self.open_line,
+            self.two,
+            self.three,
+            self.fork,
+            self.presence,
+        ]
+    }
+
+    pub(crate) fn from_weights(weights: [i16; 5]) -> Self {
+        let [open_line, two, three, fork, presence] = weights;
+        Self {
+            open_line,
+            two,
+            three,
+            fork,
+            presence,
+        }
+    }
+
+    fn parse(text: &str) -> Option<Self> {
+        let mut weights = Self::default().weights();
+        for line in text.lines().filter(|line| !line.trim().is_empty()) {
+            let (name, value) = line.split_once('=')?;
+            let position = Self::NAMES.iter().position(|&known| known == name.trim())?;
+            *weights.get_mut(position)? = value.trim().parse().ok()?;
+        }
+        Some(Self::from_weights(weights))
+    }
+
+    fn features(&self, board: &Board, mark: Mark) -> i32 {
+        let mut score = 0;
+        let mut threats: Vec<usize> = vec![];
+        for combo in board.win_combos() {
+            let own = combo
+                .iter()
+                .filter(|&&index| board.mark(index) == Some(&mark))
+                .count();
+            score += i32::from(self.presence) * Self::to_i32(own);
+            let is_open = combo.iter().all(|&index| {
+                !board.is_blocked(index) && board.mark(index) != Some(&mark.opposite())
+            });
+            if !is_open || own == 0 {
+                continue;
+            }
+            score += i32::from(self.open_line);
+            match own {
+                2 => score += i32::from(self.two),
+                3 => score += i32::from(self.three),
+                _ => {}
+            }
+            if own + 1 == combo.len() {
+                threats.extend(combo.iter().filter(|&&index| !board.is_occupied(index)));
+            }
+        }
+        threats.sort_unstable();
+        threats.dedup();
+        if threats.len() > 1 {
+            score += i32::from(self.fork);
+        }
+        score
+    }
+
+    fn to_i32(count: usize) -> i32 {
+        i32::try_from(count).unwrap_or(i32::MAX)
+    }
+}
+
+impl Default for Heuristic {
+    fn default() -> Self {
+        Self {
+            open_line: 1,
+            two: 4,
+            three: 16,
+            fork: 40,
+            presence: 1,
+        }
+    }
+}
+
+impl Display for Heuristic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, weight) in Self::NAMES.iter().zip(self.weights().iter()) {
+            writeln!(f, "{}={}", name, weight)?;
+        }
+        Ok(())
+    }
+}
+
+impl Evaluator for Heuristic {
+    fn evaluate(&self, board: &Board, mark: Mark) -> i16 {
+        let score = self.features(board, mark) - self.features(board, mark.opposite());
+        let clamped = score.clamp(-Self::LIMIT, Self::LIMIT);
+        i16::try_from(clamped).expect("clamped score fits in 16 bits")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{board, Variant};
+    use std::env;
+
+    #[test]
+    fn it_scores_an_empty_board_as_even() {
+        let board = board!["         "];
+        assert_eq!(0, Heuristic::default().evaluate(&board, Mark::X));
+        assert_eq!(0, Heuristic::default().evaluate(&board, Mark::O));
+    }
+
+    #[test]
+    fn it_scores_from_the_side_to_move() {
+        let board = board!["X        "];
+        let heuristic = Heuristic::default();
+        let score = heuristic.evaluate(&board, Mark::X);
+        assert!(score > 0);
+        assert_eq!(-score, heuristic.evaluate(&board, Mark::O));
+    }
+
+    #[test]
+    fn it_prefers_the_center() {
+        let heuristic = Heuristic::default();
+        let center = heuristic.evaluate(&board!["    X    "], Mark::X);
+        let corner = heuristic.evaluate(&board!["X        "], Mark::X);
+        let edge = heuristic.evaluate(&board![" X       "], Mark::X);
+        assert!(center > corner);
+        assert!(corner > edge);
+    }
+
+    #[test]
+    fn it_rewards_forks() {
+        let heuristic = Heuristic::default();
+        let without_forks = Heuristic {
+            fork: 0,
+            ..Heuristic::default()
+        };
+        let fork = board!["X   X X O"];
+        let single = board!["X   X   O"];
+        assert_eq!(
+            heuristic.fork,
+            heuristic.evaluate(&fork, Mark::X) - without_forks.evaluate(&fork, Mark::X)
+        );
+        assert_eq!(
+            heuristic.evaluate(&single, Mark::X),
+            without_forks.evaluate(&single, Mark::X)
+        );
+    }
+
+    #[test]
+    fn it_counts_threes_on_larger_boards() {
+        let heuristic = Heuristic {
+            open_line: 0,
+            two: 0,
+            three: 10,
+            fork: 0,
+            presence: 0,
+        };
+        let mut board = Board::with_dimensions(4, 4, 4, Variant::Standard);
+        for &index in &[0, 1, 2] {
+            board.set_mark(index, Mark::O);
+        }
+        assert_eq!(10, heuristic.evaluate(&board, Mark::O));
+        board.set_blocked(3);
+        assert_eq!(0, heuristic.evaluate(&board, Mark::O));
+    }
+
+    #[test]
+    fn it_saves_and_loads_its_weights() {
+        let path = env::temp_dir().join(format!("ttt-rs-weights-{}.txt", std::process::id()));
+        let heuristic = Heuristic {
+            open_line: 2,
+            two: 5,
+            three: 20,
+            fork: 33,
+            presence: 0,
+        };
+        heuristic.save(&path).expect("weights can be saved");
+        let loaded = Heuristic::load(&path).expect("weights can be loaded");
+        fs::remove_file(&path).expect("weight file can be removed");
+        assert_eq!(heuristic, loaded);
+    }
+
+    #[test]
+    fn it_parses_partial_weights() {
+        let expected = Heuristic {
+            fork: 12,
+            ..Heuristic::default()
+        };
+        assert_eq!(Some(expected), Heuristic::parse("fork = 12\n\n"));
+        assert_eq!(None, Heuristic::parse("bad=1"));
+        assert_eq!(None, Heuristic::parse("fork=many"));
+        assert_eq!(None, Heuristic::parse("fork"));
+    }
+}
@@ -0,0 +1,78 @@
+use crate::Move;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+pub(crate) type Table<T> = HashMap<u64, Vec<(Move, T)>>;
+
+pub(crate) fn save<T: Display>(table: &Table<T>, path: &Path) -> io::Result<()> {
+    let mut rows = table.iter().collect::<Vec<(&u64, &Vec<(Move, T)>)>>();
+    rows.sort_by_key(|&(&key, _)| key);
+    let text = rows
+        .into_iter()
+        .map(|(key, entries)| {
+            let values = entries
+                .iter()
+                .map(|(player_move, value)| format!("{}:{}", player_move, value))
+                .collect::<Vec<String>>();
+            format!("{:016x} {}\n", key, values.join(" "))
+        })
+        .collect::<Vec<String>>();
+    fs::write(path, text.concat())
+}
+
+pub(crate) fn load<T: FromStr>(path: &Path) -> io::Result<Table<T>> {
+    fs::read_to_string(path)?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| parse_row(line).ok_or_else(|| invalid_row(line)))
+        .collect()
+}
+
+fn parse_row<T: FromStr>(line: &str) -> Option<(u64, Vec<(Move, T)>)> {
+    let mut fields = line.split_whitespace();
+    let key = u64::from_str_radix(fields.next()?, 16).ok()?;
+    let entries = fields
+        .map(|field| {
+            let (player_move, value) = field.split_once(':')?;
+            Some((player_move.parse::<Move>().ok()?, value.parse::<T>().ok()?))
+        })
+        .collect::<Option<Vec<(Move, T)>>>()?;
+    Some((key, entries))
+}
+
+fn invalid_row(line: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("invalid table row: {}", line),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn it_saves_and_loads_a_table() {
+        let path = env::temp_dir().join(format!("ttt-rs-table-{}.txt", std::process::id()));
+        let mut table = Table::new();
+        table.insert(42, vec![(Move::Place(4), 3), (Move::Place(0), 0)]);
+        table.insert(7, vec![(Move::Slide { from: 6, to: 7 }, 1)]);
+        save(&table, &path).expect("table can be saved");
+        let loaded = load::<u32>(&path).expect("table can be loaded");
+        fs::remove_file(&path).expect("table file can be removed");
+        assert_eq!(table, loaded);
+    }
+
+    #[test]
+    fn it_rejects_invalid_rows() {
+        assert_eq!(None, parse_row::<u32>("zz 4:3"));
+        assert_eq!(None, parse_row::<u32>("2a 4"));
+        assert_eq!(None, parse_row::<u32>("2a bad:3"));
+        assert_eq!(Some((42, vec![(Move::Place(4), 3)])), parse_row("2a 4:3"));
+    }
+}
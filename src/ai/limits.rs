@@ -0,0 +1,19 @@
+use std::time::Duration;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SearchLimits {
+    pub max_depth: Option<usize>,
+    pub max_nodes: Option<u64>,
+    pub max_iterations: Option<u64>,
+    pub move_time: Option<Duration>,
+    pub threads: Option<usize>,
+}
+
+impl SearchLimits {
+    pub fn sequential() -> Self {
+        Self {
+            threads: Some(1),
+            ..Self::default()
+        }
+    }
+}
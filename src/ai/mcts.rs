@@ -0,0 +1,434 @@
+use crate::ai::budget::Budget;
+use crate::ai::rng::Rng;
+use crate::{Ai, Board, Mark, Move, MoveError, SearchLimits};
+#[cfg(not(target_arch = "wasm32"))]
+use std::thread;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Playout {
+    Random,
+    Heuristic,
+}
+
+#[derive(Debug)]
+pub struct Mcts {
+    playout: Playout,
+    rng: Rng,
+}
+
+struct Node {
+    player_move: Option<Move>,
+    mark: Mark,
+    children: Vec<usize>,
+    untried: Vec<Move>,
+    visits: u32,
+    reward: u32,
+}
+
+impl Mcts {
+    const DEFAULT_ITERATIONS: u64 = 10_000;
+    const MAX_PLAYOUT: usize = 200;
+    const EXPLORATION: f64 = core::f64::consts::SQRT_2;
+
+    pub fn new(playout: Playout, seed: u64) -> Self {
+        Self {
+            playout,
+            rng: Rng::new(seed),
+        }
+    }
+
+    fn best_move(
+        &self,
+        board: &Board,
+        mark: Mark,
+        limits: &SearchLimits,
+    ) -> Result<Move, MoveError> {
+        let moves = board.playable_moves(mark)?;
+        if let [only] = moves[..] {
+            return Ok(only);
+        }
+        let iterations = limits.max_iterations.or_else(|| {
+            limits
+                .move_time
+                .is_none()
+                .then_some(Self::DEFAULT_ITERATIONS)
+        });
+        let budget = Budget::new(&SearchLimits {
+            max_nodes: iterations,
+            ..*limits
+        });
+        let workers = limits.threads.unwrap_or(1).max(1);
+        let visits = self.root_search(board, mark, &moves, &budget, workers);
+        Ok(Self::most_visited(&moves, &visits))
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn root_search(
+        &self,
+        board: &Board,
+        mark: Mark,
+        moves: &[Move],
+        budget: &Budget,
+        workers: usize,
+    ) -> Vec<u32> {
+        if workers == 1 {
+            self.seq_search(board, mark, moves, budget)
+        } else {
+            self.root_parallel_search(board, mark, moves, budget, workers)
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn root_search(
+        &self,
+        board: &Board,
+        mark: Mark,
+        moves: &[Move],
+        budget: &Budget,
+        _workers: usize,
+    ) -> Vec<u32> {
+        self.seq_search(board, mark, moves, budget)
+    }
+
+    fn seq_search(&self, board: &Board, mark: Mark, moves: &[Move], budget: &Budget) -> Vec<u32> {
+        let rng = Rng::new(self.rng.next_u64());
+        let counts = self.grow_tree(&mut board.clone(), mark, budget, &rng);
+        Self::tally(moves, vec![counts])
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn root_parallel_search(
+        &self,
+        board: &Board,
+        mark: Mark,
+        moves: &[Move],
+        budget: &Budget,
+        workers: usize,
+    ) -> Vec<u32> {
+        let seeds = (0..workers)
+            .map(|_| self.rng.next_u64())
+            .collect::<Vec<u64>>();
+        let counts = thread::scope(|scope| {
+            let handles = seeds
+                .iter()
+                .map(|&seed| {
+                    let mut worker_board = board.clone();
+                    scope.spawn(move || {
+                        self.grow_tree(&mut worker_board, mark, budget, &Rng::new(seed))
+                    })
+                })
+                .collect::<Vec<thread::ScopedJoinHandle<'_, Vec<(Move, u32)>>>>();
+            handles
+                .into_iter()
+                .map(|h| h.join().expect("thread could not be joined"))
+                .collect::<Vec<Vec<(Move, u32)>>>()
+        });
+        Self::tally(moves, counts)
+    }
+
+    fn tally(moves: &[Move], counts: Vec<Vec<(Move, u32)>>) -> Vec<u32> {
+        counts
+            .into_iter()
+            .flatten()
+            .fold(vec![0; moves.len()], |mut acc, (player_move, count)| {
+                if let Some(index) = moves.iter().position(|&m| m == player_move) {
+                    acc[index] += count;
+                }
+                acc
+            })
+    }
+
+    fn grow_tree(
+        &self,
+        board: &mut Board,
+        mark: Mark,
+        budget: &Budget,
+        rng: &Rng,
+    ) -> Vec<(Move, u32)> {
+        let mut nodes = vec![Node {
+            player_move: None,
+            mark: mark.opposite(),
+            children: vec![],
+            untried: board.legal_moves(mark),
+            visits: 0,
+            reward: 0,
+        }];
+        while budget.tick() {
+            let mut path = vec![0];
+            let mut current = 0;
+            let mut applied = 0;
+            while nodes[current].untried.is_empty() && !nodes[current].children.is_empty() {
+                current = Self::select(&nodes, current);
+                let node = &nodes[current];
+                board.apply_move(node.player_move.expect("child has a move"), node.mark);
+                applied += 1;
+                path.push(current);
+            }
+            if !nodes[current].untried.is_empty() {
+                let pending = &mut nodes[current].untried;
+                let player_move = pending.swap_remove(rng.below(pending.len()));
+                let child_mark = nodes[current].mark.opposite();
+                board.apply_move(player_move, child_mark);
+                applied += 1;
+                let untried = if board.is_game_over() {
+                    vec![]
+                } else {
+                    board.legal_moves(child_mark.opposite())
+                };
+                nodes.push(Node {
+                    player_move: Some(player_move),
+                    mark: child_mark,
+                    children: vec![],
+                    untried,
+                    visits: 0,
+                    reward: 0,
+                });
+                let child = nodes.len() - 1;
+                nodes[current].children.push(child);
+                path.push(child);
+                current = child;
+            }
+            let winner = self.simulate(board, nodes[current].mark.opposite(), rng);
+            for &index in &path {
+                let node = &mut nodes[index];
+                node.visits += 1;
+                node.reward += match winner {
+                    Some(winning_mark) if winning_mark == node.mark => 2,
+                    Some(_) => 0,
+                    None => 1,
+                };
+            }
+            for _ in 0..applied {
+                board.undo_move();
+            }
+        }
+        nodes[0]
+            .children
+            .iter()
+            .map(|&child| {
+                let node = &nodes[child];
+                (node.player_move.expect("child has a move"), node.visits)
+            })
+            .collect()
+    }
+
+    fn select(nodes: &[Node], parent: usize) -> usize {
+        let parent_visits = nodes[parent].visits;
+        *nodes[parent]
+            .children
+            .iter()
+            .max_by(|&&a, &&b| {
+                Self::uct(&nodes[a], parent_visits).total_cmp(&Self::uct(&nodes[b], parent_visits))
+            })
+            .expect("node has children")
+    }
+
+    #[allow(clippy::float_arithmetic)]
+    fn uct(node: &Node, parent_visits: u32) -> f64 {
+        let visits = f64::from(node.visits);
+        let exploitation = f64::from(node.reward) / (2.0 * visits);
+        let exploration = (f64::from(parent_visits).ln() / visits).sqrt();
+        exploitation + Self::EXPLORATION * exploration
+    }
+
+    fn simulate(&self, board: &mut Board, mark: Mark, rng: &Rng) -> Option<Mark> {
+        let mut to_move = mark;
+        let mut applied = 0;
+        let mut winner = None;
+        while applied < Self::MAX_PLAYOUT {
+            if let Some(&winning_mark) = board.winner() {
+                winner = Some(winning_mark);
+                break;
+            }
+            if board.is_game_over() {
+                break;
+            }
+            let moves = board.legal_moves(to_move);
+            if moves.is_empty() {
+                winner = Some(to_move.opposite());
+                break;
+            }
+            let player_move = self.playout_move(board, to_move, &moves, rng);
+            board.apply_move(player_move, to_move);
+            applied += 1;
+            to_move = to_move.opposite();
+        }
+        for _ in 0..applied {
+            board.undo_move();
+        }
+        winner
+    }
+
+    fn playout_move(&self, board: &mut Board, mark: Mark, moves: &[Move], rng: &Rng) -> Move {
+        match self.playout {
+            Playout::Random => moves[rng.below(moves.len())],
+            Playout::Heuristic => Self::winning_move(board, mark, moves)
+                .or_else(|| Self::blocking_move(board, mark, moves))
+                .unwrap_or_else(|| moves[rng.below(moves.len())]),
+        }
+    }
+
+    fn winning_move(board: &mut Board, mark: Mark, moves: &[Move]) -> Option<Move> {
+        moves.iter().copied().find(|&player_move| {
+            board.apply_move(player_move, mark);
+            let wins = board.winner() == Some(&mark);
+            board.undo_move();
+            wins
+        })
+    }
+
+    fn blocking_move(board: &mut Board, mark: Mark, moves: &[Move]) -> Option<Move> {
+        let threats = board.legal_moves(mark.opposite());
+        let threat = Self::winning_move(board, mark.opposite(), &threats)?;
+        moves
+            .iter()
+            .copied()
+            .find(|&player_move| match (player_move, threat) {
+                (Move::Place(index), Move::Place(target)) => index == target,
+                (Move::Slide { to, .. }, Move::Slide { to: target, .. }) => to == target,
+                (Move::Place(_), Move::Slide { .. }) | (Move::Slide { .. }, Move::Place(_)) => {
+                    false
+                }
+            })
+    }
+
+    fn most_visited(moves: &[Move], visits: &[u32]) -> Move {
+        let best =
+            visits.iter().enumerate().fold(
+                0,
+                |best, (index, &count)| if count > visits[best] { index } else { best },
+            );
+        moves[best]
+    }
+}
+
+impl Default for Mcts {
+    fn default() -> Self {
+        Self {
+            playout: Playout::Heuristic,
+            rng: Rng::default(),
+        }
+    }
+}
+
+impl Ai for Mcts {
+    fn search(&self, board: &Board, mark: Mark, limits: &SearchLimits) -> Result<Move, MoveError> {
+        self.best_move(board, mark, limits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{board, Computer, Player, Variant};
+    use std::time::Duration;
+
+    fn iterations(max_iterations: u64) -> SearchLimits {
+        SearchLimits {
+            max_iterations: Some(max_iterations),
+            ..SearchLimits::default()
+        }
+    }
+
+    #[test]
+    fn it_makes_immediate_win() {
+        let board = board!["OO XX    "];
+        for &playout in &[Playout::Random, Playout::Heuristic] {
+            let mcts = Mcts::new(playout, 1);
+            assert_eq!(
+                Ok(Move::Place(2)),
+                mcts.search(&board, Mark::O, &iterations(2_000))
+            );
+        }
+    }
+
+    #[test]
+    fn it_blocks_immediate_loss() {
+        let board = board!["XX O     "];
+        let mcts = Mcts::new(Playout::Heuristic, 2);
+        assert_eq!(
+            Ok(Move::Place(2)),
+            mcts.search(&board, Mark::O, &iterations(2_000))
+        );
+    }
+
+    #[test]
+    fn it_repeats_searches_from_a_seed() {
+        let board = board!["X        "];
+        let first = Mcts::new(Playout::Random, 9);
+        let second = Mcts::new(Playout::Random, 9);
+        for _ in 0..3 {
+            assert_eq!(
+                first.search(&board, Mark::O, &iterations(300)),
+                second.search(&board, Mark::O, &iterations(300))
+            );
+        }
+    }
+
+    #[test]
+    fn it_merges_root_parallel_trees() {
+        let board = board!["OO XX    "];
+        let limits = SearchLimits {
+            max_iterations: Some(4_000),
+            threads: Some(4),
+            ..SearchLimits::default()
+        };
+        let mcts = Mcts::new(Playout::Heuristic, 3);
+        assert_eq!(Ok(Move::Place(2)), mcts.search(&board, Mark::O, &limits));
+    }
+
+    #[test]
+    fn it_limits_iterations_separately_from_nodes() {
+        let board = board!["X        "];
+        let limits = SearchLimits {
+            max_nodes: Some(1),
+            ..iterations(200)
+        };
+        let first = Mcts::new(Playout::Random, 6).search(&board, Mark::O, &limits);
+        let second = Mcts::new(Playout::Random, 6).search(&board, Mark::O, &iterations(200));
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn it_respects_a_time_budget() {
+        let board = Board::with_dimensions(5, 5, 4, Variant::Standard);
+        let limits = SearchLimits {
+            move_time: Some(Duration::from_millis(50)),
+            ..SearchLimits::default()
+        };
+        let player_move = Mcts::default()
+            .search(&board, Mark::X, &limits)
+            .expect("board is playable");
+        assert!(board.is_legal_move(player_move, Mark::X));
+    }
+
+    #[test]
+    fn it_plays_sliding_moves() {
+        let board = board!["XXOOOX   ", Variant::Sliding { pieces: 3 }];
+        let mcts = Mcts::new(Playout::Heuristic, 4);
+        let expected = Move::Slide { from: 3, to: 6 };
+        assert_eq!(
+            Ok(expected),
+            mcts.search(&board, Mark::O, &iterations(2_000))
+        );
+    }
+
+    #[test]
+    fn it_refuses_to_move_when_there_is_no_move() {
+        let full = board!["XOXXOOOXX"];
+        let result = Mcts::default().search(&full, Mark::O, &iterations(10));
+        assert_eq!(Err(MoveError::GameOver), result);
+        let blocked = board!["OXXXOOXO ", Variant::Sliding { pieces: 4 }];
+        assert_eq!(
+            Err(MoveError::NoLegalMoves),
+            Mcts::default().search(&blocked, Mark::X, &iterations(10))
+        );
+    }
+
+    #[test]
+    fn it_plugs_into_a_computer() {
+        let computer = Computer::new(Mcts::new(Playout::Heuristic, 5), Mark::O);
+        let board = board!["OO XX    "];
+        assert_eq!(Ok(Move::Place(2)), computer.get_move(&board));
+    }
+}
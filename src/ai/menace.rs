@@ -0,0 +1,196 @@
+use crate::ai::learning::{self, Table};
+use crate::ai::rng::Rng;
+use crate::{Ai, Board, Mark, Move, MoveError, Negamax, SearchLimits};
+use core::convert::TryFrom;
+use std::io;
+use std::mem;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+#[derive(Debug, Default)]
+pub struct Menace {
+    boxes: Mutex<Table<u32>>,
+    episode: Mutex<Vec<(u64, Move)>>,
+    rng: Rng,
+}
+
+impl Menace {
+    const INITIAL_BEADS: u32 = 3;
+    const WIN_BEADS: u32 = 3;
+    const DRAW_BEADS: u32 = 1;
+    const LOSS_BEADS: u32 = 1;
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            rng: Rng::new(seed),
+            ..Self::default()
+        }
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(Self {
+            boxes: Mutex::new(learning::load(path.as_ref())?),
+            ..Self::default()
+        })
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        learning::save(&self.boxes(), path.as_ref())
+    }
+
+    pub fn positions(&self) -> usize {
+        self.boxes().len()
+    }
+
+    pub fn beads(&self, board: &Board, mark: Mark, player_move: Move) -> u32 {
+        self.boxes()
+            .get(&Negamax::table_key(board, mark))
+            .and_then(|beads| beads.iter().find(|&&(bead, _)| bead == player_move))
+            .map_or(0, |&(_, count)| count)
+    }
+
+    fn boxes(&self) -> MutexGuard<'_, Table<u32>> {
+        self.boxes.lock().expect("matchboxes are not poisoned")
+    }
+
+    fn choose(&self, board: &Board, mark: Mark, moves: &[Move]) -> Move {
+        let key = Negamax::table_key(board, mark);
+        let mut boxes = self.boxes();
+        let beads = boxes.entry(key).or_default();
+        beads.retain(|(bead, _)| moves.contains(bead));
+        if beads.len() != moves.len() || beads.iter().all(|&(_, count)| count == 0) {
+            *beads = moves
+                .iter()
+                .map(|&player_move| (player_move, Self::INITIAL_BEADS))
+                .collect();
+        }
+
+        let total = beads
+            .iter()
+            .map(|&(_, count)| u64::from(count))
+            .sum::<u64>();
+        let mut draw = u64::try_from(
+            self.rng
+                .below(usize::try_from(total).expect("bead count fits in usize")),
+        )
+        .expect("draw fits in 64 bits");
+        let chosen = beads
+            .iter()
+            .find(|&&(_, count)| {
+                if draw < u64::from(count) {
+                    true
+                } else {
+                    draw = draw.saturating_sub(u64::from(count));
+                    false
+                }
+            })
+            .map(|&(player_move, _)| player_move)
+            .expect("a bead is drawn from the box");
+        self.episode
+            .lock()
+            .expect("episode is not poisoned")
+            .push((key, chosen));
+        chosen
+    }
+}
+
+impl Ai for Menace {
+    fn search(&self, board: &Board, mark: Mark, _limits: &SearchLimits) -> Result<Move, MoveError> {
+        let moves = board.playable_moves(mark)?;
+        Ok(self.choose(board, mark, &moves))
+    }
+
+    fn game_over(&self, _board: &Board, mark: Mark, winner: Option<Mark>) {
+        let episode = mem::take(&mut *self.episode.lock().expect("episode is not poisoned"));
+        let mut boxes = self.boxes();
+        for (key, player_move) in episode {
+            let bead = boxes
+                .get_mut(&key)
+                .and_then(|beads| beads.iter_mut().find(|&&mut (bead, _)| bead == player_move));
+            if let Some((_, count)) = bead {
+                *count = match winner {
+                    Some(winning_mark) if winning_mark == mark => {
+                        count.saturating_add(Self::WIN_BEADS)
+                    }
+                    Some(_) => count.saturating_sub(Self::LOSS_BEADS),
+                    None => count.saturating_add(Self::DRAW_BEADS),
+                };
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_common::new_board;
+    use crate::{board, Variant};
+    use std::env;
+    use std::fs;
+
+    #[test]
+    fn it_plays_legal_moves() {
+        let menace = Menace::with_seed(1);
+        let board = new_board();
+        let player_move = menace
+            .search(&board, Mark::X, &SearchLimits::default())
+            .expect("board is playable");
+        assert!(board.is_legal_move(player_move, Mark::X));
+        assert_eq!(1, menace.positions());
+    }
+
+    #[test]
+    fn it_learns_to_take_a_winning_move() {
+        let menace = Menace::with_seed(7);
+        let board = board!["XX OO    "];
+        for _ in 0..100 {
+            let player_move = menace
+                .search(&board, Mark::X, &SearchLimits::default())
+                .expect("board is playable");
+            let winner = if player_move == Move::Place(2) {
+                Mark::X
+            } else {
+                Mark::O
+            };
+            menace.game_over(&board, Mark::X, Some(winner));
+        }
+        let winning_beads = menace.beads(&board, Mark::X, Move::Place(2));
+        for &index in &[5, 6, 7, 8] {
+            assert!(winning_beads > menace.beads(&board, Mark::X, Move::Place(index)));
+        }
+    }
+
+    #[test]
+    fn it_forgets_the_episode_after_the_game() {
+        let menace = Menace::with_seed(3);
+        let board = new_board();
+        let player_move = menace
+            .search(&board, Mark::X, &SearchLimits::default())
+            .expect("board is playable");
+        menace.game_over(&board, Mark::X, None);
+        menace.game_over(&board, Mark::X, None);
+        assert_eq!(
+            Menace::INITIAL_BEADS + Menace::DRAW_BEADS,
+            menace.beads(&board, Mark::X, player_move)
+        );
+    }
+
+    #[test]
+    fn it_saves_and_loads_its_matchboxes() {
+        let path = env::temp_dir().join(format!("ttt-rs-menace-{}.txt", std::process::id()));
+        let menace = Menace::with_seed(5);
+        let board = new_board();
+        let player_move = menace
+            .search(&board, Mark::X, &SearchLimits::default())
+            .expect("board is playable");
+        menace.game_over(&board, Mark::X, Some(Mark::X));
+        menace.save(&path).expect("matchboxes can be saved");
+        let loaded = Menace::load(&path).expect("matchboxes can be loaded");
+        fs::remove_file(&path).expect("matchbox file can be removed");
+        assert_eq!(1, loaded.positions());
+        assert_eq!(
+            Menace::INITIAL_BEADS + Menace::WIN_BEADS,
+            loaded.beads(&board, Mark::X, player_move)
+        );
+    }
+}
@@ -1,9 +1,68 @@
-use crate::{Board, Mark};
+use crate::{Board, Mark, Move, MoveError};
+use core::fmt::Debug;
 
+mod analysis;
+mod budget;
+mod control;
+mod difficulty;
+mod evaluator;
+mod learning;
+mod limits;
+mod mcts;
+mod menace;
 mod negamax;
+mod ordering;
+#[cfg(not(target_arch = "wasm32"))]
+mod pool;
+mod proof_number;
+mod q_learning;
+mod rng;
+mod solver;
+mod stats;
+mod tablebase;
+mod threat_space;
+mod transposition;
+mod tuning;
 
-pub(crate) use negamax::Negamax;
+pub use analysis::{Analysis, Hint, MoveScore, Outcome, SearchResult};
+#[cfg(not(target_arch = "wasm32"))]
+pub use control::SearchHandle;
+pub use control::{CancelToken, Progress};
+pub use difficulty::{Difficulty, Handicap};
+pub use evaluator::{Evaluator, Heuristic};
+pub use limits::SearchLimits;
+pub use mcts::{Mcts, Playout};
+pub use menace::Menace;
+pub use negamax::Negamax;
+pub use proof_number::ProofNumber;
+pub use q_learning::QLearning;
+pub use solver::{Solution, Solver};
+pub use stats::SearchStats;
+pub use tablebase::{Tablebase, TablebaseEntry};
+pub use threat_space::ThreatSpace;
+pub use tuning::Tuner;
 
 pub trait Ai {
-    fn search(&self, board: &Board, mark: Mark) -> usize;
+    fn search(&self, board: &Board, mark: Mark, limits: &SearchLimits) -> Result<Move, MoveError>;
+
+    fn stats(&self) -> Option<SearchStats> {
+        None
+    }
+
+    fn hint(&self, board: &Board, mark: Mark, limits: &SearchLimits) -> Result<Hint, MoveError> {
+        let player_move = self.search(board, mark, limits)?;
+        Ok(Hint {
+            player_move,
+            score: None,
+            outcome: Outcome::Unknown,
+        })
+    }
+
+    fn game_over(&self, _board: &Board, _mark: Mark, _winner: Option<Mark>) {}
+}
+
+impl Debug for dyn Ai {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Ai")
+    }
 }
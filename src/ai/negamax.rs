@@ -1,111 +1,832 @@
-use crate::{Ai, Board, Mark};
+use crate::ai::budget::Budget;
+use crate::ai::ordering::MoveOrdering;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::ai::pool::WorkerPool;
+use crate::ai::rng::Rng;
+use crate::ai::stats::Counters;
+use crate::ai::transposition::{Bound, Entry, TranspositionTable};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::SearchHandle;
+use crate::{
+    Ai, Analysis, Board, CancelToken, Difficulty, Evaluator, Handicap, Heuristic, Hint, Mark, Move,
+    MoveError, MoveScore, Outcome, Progress, SearchLimits, SearchResult, SearchStats, Solution,
+    Solver, Variant,
+};
+use core::cell::RefCell;
 use core::cmp;
+use core::convert::TryFrom;
+#[cfg(not(target_arch = "wasm32"))]
+use core::num::NonZeroUsize;
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex, OnceLock};
+#[cfg(not(target_arch = "wasm32"))]
 use std::thread;
+use std::time::{Duration, Instant};
 
-#[derive(Debug, Default)]
-pub struct Negamax;
+#[derive(Debug)]
+pub struct Negamax {
+    table: OnceLock<Arc<TranspositionTable>>,
+    table_owner: Mutex<Option<TableOwner>>,
+    #[cfg(not(target_arch = "wasm32"))]
+    pool: Mutex<Option<Arc<WorkerPool>>>,
+    evaluator: Arc<dyn Evaluator>,
+    handicap: Handicap,
+    rng: Rng,
+    random_ties: bool,
+    move_ordering: bool,
+    stats: Mutex<SearchStats>,
+}
 
 impl Negamax {
     const MIN: i16 = -999;
     const MAX: i16 = 999;
     const DEFAULT_DEPTH: i16 = 5;
+    const GRAVITY_DEPTH: i16 = 7;
+    const MAX_DEPTH: i16 = 64;
+    const DECISIVE: i16 = Self::MAX - 2 * Self::MAX_DEPTH;
+    const X_TO_MOVE_KEY: u64 = 0x5851_f42d_4c95_7f2d;
+
+    pub fn with_difficulty(difficulty: Difficulty) -> Self {
+        Self {
+            handicap: Handicap::from(difficulty),
+            random_ties: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_handicap(handicap: Handicap, seed: u64) -> Self {
+        Self {
+            handicap,
+            rng: Rng::new(seed),
+            random_ties: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_move_ordering(move_ordering: bool) -> Self {
+        Self {
+            move_ordering,
+            ..Self::default()
+        }
+    }
+
+    pub fn nodes(&self) -> u64 {
+        self.stats.lock().expect("stats lock is poisoned").nodes
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_handicap(Handicap::default(), seed)
+    }
+
+    pub fn with_evaluator<E: Evaluator + 'static>(evaluator: E) -> Self {
+        Self {
+            evaluator: Arc::new(evaluator),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_weights<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(Self::with_evaluator(Heuristic::load(path)?))
+    }
+
+    pub fn analyse(
+        &self,
+        board: &Board,
+        mark: Mark,
+        limits: &SearchLimits,
+    ) -> Result<Analysis, MoveError> {
+        board.playable_moves(mark)?;
+        let (searcher, scores, depth) =
+            self.run(board, mark, limits, Budget::new(limits), &mut |_| {});
+        let complete = Self::is_complete(board, depth);
+        let best_move = Self::best_score(scores.clone());
+        let score = scores
+            .iter()
+            .find(|&&(player_move, _)| player_move == best_move)
+            .map_or(0, |&(_, score)| score);
+        Ok(Analysis {
+            moves: scores
+                .into_iter()
+                .map(|(player_move, move_score)| MoveScore {
+                    player_move,
+                    score: move_score,
+                    outcome: Self::outcome(move_score, complete),
+                })
+                .collect(),
+            best_move,
+            score,
+            outcome: Self::outcome(score, complete),
+            principal_variation: searcher.principal_variation(board, mark, best_move),
+            depth: usize::try_from(depth + 1).unwrap_or(0),
+            stats: searcher.stats(),
+        })
+    }
+
+    pub fn search_with_progress<F>(
+        &self,
+        board: &Board,
+        mark: Mark,
+        limits: &SearchLimits,
+        cancel: &CancelToken,
+        mut on_progress: F,
+    ) -> Result<SearchResult, MoveError>
+    where
+        F: FnMut(&Progress),
+    {
+        let moves = board.playable_moves(mark)?;
+        if let [only] = moves[..] {
+            return Ok(SearchResult {
+                best_move: only,
+                stats: SearchStats::default(),
+            });
+        }
+        let budget = Budget::with_cancel(limits, cancel);
+        let (searcher, scores, _) = self.run(board, mark, limits, budget, &mut on_progress);
+        Ok(SearchResult {
+            best_move: self.choose(scores),
+            stats: searcher.stats(),
+        })
+    }
 
     #[cfg(not(target_arch = "wasm32"))]
-    fn search(board: &Board, mark: Mark) -> usize {
-        let handles = Self::concur_search(board, mark);
+    pub fn spawn_search<F>(
+        self: &Arc<Self>,
+        board: &Board,
+        mark: Mark,
+        limits: SearchLimits,
+        on_progress: F,
+    ) -> SearchHandle
+    where
+        F: FnMut(&Progress) + Send + 'static,
+    {
+        let negamax = Arc::clone(self);
+        let position = board.clone();
+        SearchHandle::spawn(CancelToken::new(), move |cancel| {
+            negamax.search_with_progress(&position, mark, &limits, cancel, on_progress)
+        })
+    }
 
-        let scores = handles.into_iter().fold(vec![], |mut acc, h| {
-            acc.push(h.join().expect("thread could not be joined"));
-            acc
-        });
-        Self::best_score(scores)
+    fn search(&self, board: &Board, mark: Mark, limits: &SearchLimits) -> Move {
+        let moves = board.legal_moves(mark);
+        if moves.len() == 1 {
+            self.record(SearchStats::default());
+            return moves[0];
+        }
+        let (searcher, scores, _) = self.run(board, mark, limits, Budget::new(limits), &mut |_| {});
+        self.record(searcher.stats());
+        self.choose(scores)
     }
 
-    #[cfg(target_arch = "wasm32")]
-    fn search(board: &Board, mark: Mark) -> usize {
-        let scores = Self::seq_search(board, mark);
-        Self::best_score(scores)
+    fn run(
+        &self,
+        board: &Board,
+        mark: Mark,
+        limits: &SearchLimits,
+        budget: Budget,
+        report: &mut dyn FnMut(&Progress),
+    ) -> (Searcher, Vec<(Move, i16)>, i16) {
+        let searcher = self.searcher_with(board, limits, budget);
+        let max_depth = limits
+            .max_depth
+            .into_iter()
+            .chain(self.handicap.max_depth)
+            .min()
+            .map(Self::clamp_depth);
+        let (scores, depth) = if searcher.budget.is_limited() {
+            let cap = max_depth.map_or_else(
+                || Self::max_depth(board),
+                |depth| cmp::min(depth, Self::max_depth(board)),
+            );
+            Self::deepen(&searcher, board, mark, cap, report)
+        } else if searcher.budget.is_cancellable() {
+            let cap = max_depth.unwrap_or_else(|| Self::depth(board));
+            Self::deepen(&searcher, board, mark, cap, report)
+        } else {
+            let depth = max_depth.unwrap_or_else(|| Self::depth(board));
+            let scores = Self::root_scores(&searcher, board, mark, depth)
+                .expect("unlimited search ran out of budget");
+            (scores, depth)
+        };
+        (searcher, scores, depth)
+    }
+
+    fn record(&self, stats: SearchStats) {
+        *self.stats.lock().expect("stats lock is poisoned") = stats;
+    }
+
+    fn searcher(&self, board: &Board, limits: &SearchLimits) -> Searcher {
+        self.searcher_with(board, limits, Budget::new(limits))
+    }
+
+    #[cfg_attr(target_arch = "wasm32", allow(unused_variables))]
+    fn searcher_with(&self, board: &Board, limits: &SearchLimits, budget: Budget) -> Searcher {
+        self.claim_table(board);
+        Searcher {
+            #[cfg(not(target_arch = "wasm32"))]
+            pool: self.pool(Self::workers(limits) - 1),
+            // Repetition draws depend on the path to a position, so cached
+            // scores cannot be shared between paths in those variants.
+            table: (!board.can_repeat()).then(|| self.table()),
+            evaluator: Arc::clone(&self.evaluator),
+            budget: Arc::new(budget),
+            counters: Arc::default(),
+            root_times: RefCell::default(),
+            start: Instant::now(),
+            halt: Arc::default(),
+            ordering: self
+                .move_ordering
+                .then(|| RefCell::new(MoveOrdering::new(board))),
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn pool(&self, helpers: usize) -> Option<Arc<WorkerPool>> {
+        if helpers == 0 {
+            return None;
+        }
+        let mut pool = self.pool.lock().expect("pool lock is poisoned");
+        if let Some(existing) = pool.as_ref().filter(|existing| existing.size() == helpers) {
+            return Some(Arc::clone(existing));
+        }
+        let created = Arc::new(WorkerPool::new(helpers));
+        *pool = Some(Arc::clone(&created));
+        Some(created)
+    }
+
+    fn claim_table(&self, board: &Board) {
+        let owner = TableOwner::of(board);
+        let mut current = self
+            .table_owner
+            .lock()
+            .expect("table owner lock is poisoned");
+        if current.is_some_and(|previous| !previous.continues_with(&owner)) {
+            if let Some(table) = self.table.get() {
+                table.clear();
+            }
+        }
+        *current = Some(owner);
     }
 
-    fn concur_search(board: &Board, mark: Mark) -> Vec<thread::JoinHandle<(usize, i16)>> {
+    fn table(&self) -> Arc<TranspositionTable> {
+        Arc::clone(self.table.get_or_init(Arc::default))
+    }
+
+    fn deepen(
+        searcher: &Searcher,
+        board: &Board,
+        mark: Mark,
+        cap: i16,
+        report: &mut dyn FnMut(&Progress),
+    ) -> (Vec<(Move, i16)>, i16) {
+        let start = Instant::now();
+        let mut completed = (Self::static_scores(searcher, board, mark), -1);
+        for depth in 0..=cap {
+            match Self::root_scores(searcher, board, mark, depth) {
+                Some(scores) => {
+                    report(&Progress {
+                        depth: usize::from(depth.unsigned_abs()) + 1,
+                        nodes: searcher.budget.nodes(),
+                        best_move: Some(Self::best_score(scores.clone())),
+                        elapsed: start.elapsed(),
+                    });
+                    completed = (scores, depth);
+                }
+                None => break,
+            }
+        }
+        completed
+    }
+
+    fn static_scores(searcher: &Searcher, board: &Board, mark: Mark) -> Vec<(Move, i16)> {
+        let mut position = board.clone();
         board
-            .empty_cell_indices()
+            .legal_moves(mark)
             .into_iter()
-            .fold(vec![], |mut acc, index| {
-                let mut new_board = board.clone();
-                acc.push(thread::spawn(move || {
-                    new_board.set_mark(index, mark);
-                    let score = -Self::negamax_init(&mut new_board, mark.opposite());
-                    (index, score)
-                }));
-                acc
+            .map(|player_move| {
+                position.apply_move(player_move, mark);
+                let score = -searcher.score(&position, mark.opposite(), 1);
+                position.undo_move();
+                (player_move, score)
             })
+            .collect()
     }
 
-    #[allow(dead_code)]
-    fn seq_search(board: &Board, mark: Mark) -> Vec<(usize, i16)> {
-        board
-            .empty_cell_indices()
+    fn prove(
+        searcher: &Searcher,
+        board: &Board,
+        mark: Mark,
+        cap: i16,
+    ) -> Option<(Vec<(Move, i16)>, i16)> {
+        let mut completed = None;
+        for depth in 0..=cap {
+            match Self::root_scores(searcher, board, mark, depth) {
+                Some(scores) => {
+                    let decided = Self::is_decided(board, &scores, depth);
+                    completed = Some((scores, depth));
+                    if decided {
+                        break;
+                    }
+                }
+                None => break,
+            }
+        }
+        completed
+    }
+
+    fn is_decided(board: &Board, scores: &[(Move, i16)], depth: i16) -> bool {
+        Self::is_complete(board, depth)
+            || scores.iter().any(|&(_, score)| score > Self::DECISIVE)
+            || scores.iter().all(|&(_, score)| score < -Self::DECISIVE)
+    }
+
+    fn is_complete(board: &Board, depth: i16) -> bool {
+        match board.variant() {
+            Variant::Standard | Variant::Gravity => {
+                Self::clamp_depth(board.empty_cell_indices().len()) <= depth + 1
+            }
+            Variant::Sliding { .. } | Variant::Disappearing { .. } => false,
+        }
+    }
+
+    fn choose(&self, scores: Vec<(Move, i16)>) -> Move {
+        if self.handicap.is_perfect() && !self.random_ties {
+            return Self::best_score(scores);
+        }
+        if self.rng.chance(self.handicap.mistake_percent) {
+            return scores[self.rng.below(scores.len())].0;
+        }
+        let best = scores.iter().map(|&(_, score)| score).max().unwrap_or(0);
+        let margin = if (-Self::DECISIVE..=Self::DECISIVE).contains(&best) {
+            self.handicap.margin
+        } else {
+            0
+        };
+        let near_best = scores
             .into_iter()
-            .fold(vec![], |mut acc, index| {
-                let mut new_board = board.clone();
-                new_board.set_mark(index, mark);
-                let score = -Self::negamax_init(&mut new_board, mark.opposite());
-                acc.push((index, score));
-                acc
-            })
+            .filter(|&(_, score)| score >= best - margin)
+            .collect::<Vec<(Move, i16)>>();
+        near_best[self.rng.below(near_best.len())].0
     }
 
-    fn negamax_init(board: &mut Board, mark: Mark) -> i16 {
-        Self::negamax(board, mark, Self::DEFAULT_DEPTH, Self::MIN, Self::MAX)
+    fn root_scores(
+        searcher: &Searcher,
+        board: &Board,
+        mark: Mark,
+        depth: i16,
+    ) -> Option<Vec<(Move, i16)>> {
+        let scores = Self::root_search(searcher, board, mark, depth);
+        if searcher.budget.is_exhausted() {
+            None
+        } else {
+            Some(scores)
+        }
     }
 
-    fn negamax(board: &mut Board, mark: Mark, depth: i16, alpha: i16, beta: i16) -> i16 {
-        if depth == 0 || board.is_game_over() {
-            Self::score(board, mark)
+    #[cfg(not(target_arch = "wasm32"))]
+    fn root_search(searcher: &Searcher, board: &Board, mark: Mark, depth: i16) -> Vec<(Move, i16)> {
+        if let Some(pool) = &searcher.pool {
+            Self::lazy_smp_search(searcher, pool, board, mark, depth)
         } else {
-            let mut alpha_mut = alpha;
-            for index in board.empty_cell_indices() {
-                board.set_mark(index, mark);
-                let score = -Self::negamax(board, mark.opposite(), depth - 1, -beta, -alpha_mut);
-                board.reset_mark(index);
-                alpha_mut = cmp::max(score + depth, alpha_mut);
-                if alpha_mut >= beta {
-                    break;
-                }
+            Self::seq_search(searcher, board, mark, depth)
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn root_search(searcher: &Searcher, board: &Board, mark: Mark, depth: i16) -> Vec<(Move, i16)> {
+        Self::seq_search(searcher, board, mark, depth)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn lazy_smp_search(
+        searcher: &Searcher,
+        pool: &WorkerPool,
+        board: &Board,
+        mark: Mark,
+        depth: i16,
+    ) -> Vec<(Move, i16)> {
+        let moves = board.legal_moves(mark);
+        let halt = Arc::new(AtomicBool::new(false));
+        let (done, finished) = mpsc::channel();
+        for worker in 1..=pool.size() {
+            let helper = searcher.helper(&halt);
+            let mut helper_board = board.clone();
+            let mut helper_moves = moves.clone();
+            helper_moves.rotate_left(worker % moves.len().max(1));
+            let helper_done = done.clone();
+            pool.execute(move || {
+                helper.score_moves(&mut helper_board, mark, &helper_moves, depth);
+                helper_done.send(()).expect("search waits for its helpers");
+            });
+        }
+        drop(done);
+        let scores = searcher.score_moves(&mut board.clone(), mark, &moves, depth);
+        halt.store(true, Ordering::Relaxed);
+        for _ in 0..pool.size() {
+            finished.recv().expect("helper search panicked");
+        }
+        scores
+    }
+
+    fn seq_search(searcher: &Searcher, board: &Board, mark: Mark, depth: i16) -> Vec<(Move, i16)> {
+        let moves = board.legal_moves(mark);
+        let mut new_board = board.clone();
+        searcher.score_moves(&mut new_board, mark, &moves, depth)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn workers(limits: &SearchLimits) -> usize {
+        limits
+            .threads
+            .unwrap_or_else(|| thread::available_parallelism().map_or(1, NonZeroUsize::get))
+            .max(1)
+    }
+
+    fn depth(board: &Board) -> i16 {
+        match board.variant() {
+            Variant::Gravity => Self::GRAVITY_DEPTH,
+            Variant::Standard | Variant::Sliding { .. } | Variant::Disappearing { .. } => {
+                Self::DEFAULT_DEPTH
             }
-            alpha_mut
         }
     }
 
-    fn score(board: &Board, mark: Mark) -> i16 {
-        if let Some(winner) = board.winner() {
-            if *winner == mark {
-                Self::MAX
-            } else {
-                Self::MIN
+    fn max_depth(board: &Board) -> i16 {
+        match board.variant() {
+            Variant::Standard | Variant::Gravity => {
+                Self::clamp_depth(board.empty_cell_indices().len())
             }
+            Variant::Sliding { .. } | Variant::Disappearing { .. } => Self::MAX_DEPTH,
+        }
+    }
+
+    fn clamp_depth(depth: usize) -> i16 {
+        i16::try_from(depth).map_or(Self::MAX_DEPTH, |clamped| {
+            cmp::min(clamped, Self::MAX_DEPTH)
+        })
+    }
+
+    fn to_table(score: i16, ply: i16) -> i16 {
+        if score > Self::DECISIVE {
+            score + ply
+        } else if score < -Self::DECISIVE {
+            score - ply
         } else {
-            0
+            score
+        }
+    }
+
+    fn from_table(score: i16, ply: i16) -> i16 {
+        if score > Self::DECISIVE {
+            score - ply
+        } else if score < -Self::DECISIVE {
+            score + ply
+        } else {
+            score
+        }
+    }
+
+    fn outcome(score: i16, complete: bool) -> Outcome {
+        if score > Self::DECISIVE {
+            Outcome::Win {
+                plies: usize::from((Self::MAX - score).unsigned_abs()),
+            }
+        } else if score < -Self::DECISIVE {
+            Outcome::Loss {
+                plies: usize::from((score - Self::MIN).unsigned_abs()),
+            }
+        } else if complete {
+            Outcome::Draw
+        } else {
+            Outcome::Unknown
+        }
+    }
+
+    pub(crate) fn score_of(outcome: Outcome) -> i16 {
+        match outcome {
+            Outcome::Win { plies } => Self::MAX - i16::try_from(plies).unwrap_or(Self::MAX),
+            Outcome::Loss { plies } => Self::MIN + i16::try_from(plies).unwrap_or(Self::MAX),
+            Outcome::Draw | Outcome::Unknown => 0,
+        }
+    }
+
+    pub(crate) fn table_key(board: &Board, mark: Mark) -> u64 {
+        match mark {
+            Mark::O => board.position_key(),
+            Mark::X => board.position_key() ^ Self::X_TO_MOVE_KEY,
         }
     }
 
-    fn best_score(mut scores: Vec<(usize, i16)>) -> usize {
+    fn best_score(mut scores: Vec<(Move, i16)>) -> Move {
         scores.sort_by(|a, b| b.1.cmp(&a.1));
         scores[0].0
     }
 }
 
+impl Default for Negamax {
+    fn default() -> Self {
+        Self {
+            table: OnceLock::new(),
+            table_owner: Mutex::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            pool: Mutex::default(),
+            evaluator: Arc::new(Heuristic::default()),
+            handicap: Handicap::default(),
+            rng: Rng::default(),
+            random_ties: false,
+            move_ordering: true,
+            stats: Mutex::default(),
+        }
+    }
+}
+
+impl Solver for Negamax {
+    fn solve(&self, board: &Board, mark: Mark, limits: &SearchLimits) -> Solution {
+        if let Some(solution) = Solution::terminal(board, mark) {
+            return solution;
+        }
+        let searcher = self.searcher(board, limits);
+        let cap = limits.max_depth.map_or_else(
+            || Self::max_depth(board),
+            |depth| cmp::min(Self::clamp_depth(depth), Self::max_depth(board)),
+        );
+        match Self::prove(&searcher, board, mark, cap) {
+            Some((scores, depth)) => {
+                let best_move = Self::best_score(scores.clone());
+                let score = scores
+                    .iter()
+                    .find(|&&(player_move, _)| player_move == best_move)
+                    .map_or(0, |&(_, move_score)| move_score);
+                let outcome = Self::outcome(score, Self::is_complete(board, depth));
+                Solution {
+                    outcome,
+                    best_move: Some(best_move),
+                    score,
+                    proven: outcome != Outcome::Unknown,
+                    depth: usize::from(depth.unsigned_abs()) + 1,
+                    stats: searcher.stats(),
+                }
+            }
+            None => Solution {
+                outcome: Outcome::Unknown,
+                best_move: board.legal_moves(mark).first().copied(),
+                score: 0,
+                proven: false,
+                depth: 0,
+                stats: searcher.stats(),
+            },
+        }
+    }
+}
+
 impl Ai for Negamax {
-    fn search(&self, board: &Board, mark: Mark) -> usize {
-        Self::search(board, mark)
+    fn search(&self, board: &Board, mark: Mark, limits: &SearchLimits) -> Result<Move, MoveError> {
+        board
+            .playable_moves(mark)
+            .map(|_| Self::search(self, board, mark, limits))
+    }
+
+    fn hint(&self, board: &Board, mark: Mark, limits: &SearchLimits) -> Result<Hint, MoveError> {
+        let analysis = self.analyse(board, mark, limits)?;
+        Ok(Hint {
+            player_move: analysis.best_move,
+            score: Some(analysis.score),
+            outcome: analysis.outcome,
+        })
+    }
+
+    fn stats(&self) -> Option<SearchStats> {
+        Some(self.stats.lock().expect("stats lock is poisoned").clone())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct TableOwner {
+    size: usize,
+    row_size: usize,
+    win_length: usize,
+    variant: Variant,
+    plies: usize,
+}
+
+impl TableOwner {
+    fn of(board: &Board) -> Self {
+        Self {
+            size: board.size(),
+            row_size: board.row_size(),
+            win_length: board.win_length(),
+            variant: board.variant(),
+            plies: board.ply_count(),
+        }
+    }
+
+    fn continues_with(&self, next: &Self) -> bool {
+        self.size == next.size
+            && self.row_size == next.row_size
+            && self.win_length == next.win_length
+            && self.variant == next.variant
+            && self.plies <= next.plies
+    }
+}
+
+#[derive(Clone)]
+struct Searcher {
+    #[cfg(not(target_arch = "wasm32"))]
+    pool: Option<Arc<WorkerPool>>,
+    table: Option<Arc<TranspositionTable>>,
+    evaluator: Arc<dyn Evaluator>,
+    budget: Arc<Budget>,
+    halt: Arc<AtomicBool>,
+    ordering: Option<RefCell<MoveOrdering>>,
+    counters: Arc<Counters>,
+    root_times: RefCell<Vec<(Move, Duration)>>,
+    start: Instant,
+}
+
+impl Searcher {
+    #[cfg(not(target_arch = "wasm32"))]
+    fn helper(&self, halt: &Arc<AtomicBool>) -> Self {
+        Self {
+            halt: Arc::clone(halt),
+            ..self.clone()
+        }
+    }
+
+    fn is_stopped(&self) -> bool {
+        self.budget.is_exhausted() || self.halt.load(Ordering::Relaxed)
+    }
+
+    fn score_moves(
+        &self,
+        board: &mut Board,
+        mark: Mark,
+        moves: &[Move],
+        depth: i16,
+    ) -> Vec<(Move, i16)> {
+        moves.iter().fold(vec![], |mut acc, &player_move| {
+            let start = Instant::now();
+            board.apply_move(player_move, mark);
+            let score = -self.negamax(board, mark.opposite(), depth, 1, Negamax::MIN, Negamax::MAX);
+            board.undo_move();
+            self.record_root_time(player_move, start.elapsed());
+            acc.push((player_move, score));
+            acc
+        })
+    }
+
+    fn record_root_time(&self, player_move: Move, elapsed: Duration) {
+        let mut root_times = self.root_times.borrow_mut();
+        match root_times
+            .iter_mut()
+            .find(|(timed, _)| *timed == player_move)
+        {
+            Some((_, total)) => *total += elapsed,
+            None => root_times.push((player_move, elapsed)),
+        }
+    }
+
+    fn stats(&self) -> SearchStats {
+        self.counters.stats(
+            self.budget.nodes(),
+            self.start.elapsed(),
+            self.root_times.borrow().clone(),
+        )
+    }
+
+    fn negamax(
+        &self,
+        board: &mut Board,
+        mark: Mark,
+        depth: i16,
+        ply: i16,
+        alpha: i16,
+        beta: i16,
+    ) -> i16 {
+        if !self.budget.tick() || self.halt.load(Ordering::Relaxed) {
+            return 0;
+        }
+        if depth == 0 || board.is_game_over() {
+            return self.score(board, mark, ply);
+        }
+        let key = Negamax::table_key(board, mark);
+        let mut alpha_mut = alpha;
+        let mut beta_mut = beta;
+        let probed = self.table.as_ref().and_then(|table| table.probe(key));
+        if let Some(exact) = probed.filter(|entry| entry.depth == depth) {
+            self.counters.record_table_hit();
+            let score = Negamax::from_table(exact.score, ply);
+            match exact.bound {
+                Bound::Exact => return score,
+                Bound::Lower => alpha_mut = cmp::max(alpha_mut, score),
+                Bound::Upper => beta_mut = cmp::min(beta_mut, score),
+            }
+            if alpha_mut >= beta_mut {
+                return score;
+            }
+        }
+        let mut moves = board.legal_moves(mark);
+        if moves.is_empty() {
+            return Negamax::MIN + ply;
+        }
+        if let Some(ordering) = &self.ordering {
+            let table_move = probed.and_then(|entry| entry.best);
+            ordering
+                .borrow()
+                .order(board, mark, &mut moves, depth, table_move);
+        }
+        let window = (alpha_mut, beta_mut);
+        let mut best = None;
+        for player_move in moves {
+            board.apply_move(player_move, mark);
+            let score = -self.negamax(
+                board,
+                mark.opposite(),
+                depth - 1,
+                ply + 1,
+                -beta_mut,
+                -alpha_mut,
+            );
+            board.undo_move();
+            if score > alpha_mut {
+                alpha_mut = score;
+                best = Some(player_move);
+            }
+            if alpha_mut >= beta_mut {
+                self.counters.record_cutoff();
+                if let Some(ordering) = &self.ordering {
+                    ordering.borrow_mut().record_cutoff(player_move, depth);
+                }
+                break;
+            }
+        }
+        if self.is_stopped() {
+            return alpha_mut;
+        }
+        if let Some(table) = &self.table {
+            table.store(Entry {
+                key,
+                depth,
+                score: Negamax::to_table(alpha_mut, ply),
+                bound: Entry::bound_for(alpha_mut, window.0, window.1),
+                best,
+            });
+        }
+        alpha_mut
+    }
+
+    fn score(&self, board: &Board, mark: Mark, ply: i16) -> i16 {
+        self.counters.record_leaf();
+        if let Some(winner) = board.winner() {
+            if *winner == mark {
+                Negamax::MAX - ply
+            } else {
+                Negamax::MIN + ply
+            }
+        } else if board.is_game_over() {
+            0
+        } else {
+            self.evaluator.evaluate(board, mark)
+        }
+    }
+
+    fn principal_variation(&self, board: &Board, mark: Mark, first: Move) -> Vec<Move> {
+        let mut line = vec![first];
+        let mut position = board.clone();
+        let mut to_move = mark;
+        position.apply_move(first, to_move);
+        to_move = to_move.opposite();
+        while !position.is_game_over()
+            && line.len() < usize::from(Negamax::MAX_DEPTH.unsigned_abs())
+        {
+            let key = Negamax::table_key(&position, to_move);
+            let next = self
+                .table
+                .as_ref()
+                .and_then(|table| table.probe(key))
+                .and_then(|entry| entry.best)
+                .filter(|&player_move| position.is_legal_move(player_move, to_move));
+            match next {
+                Some(player_move) => {
+                    position.apply_move(player_move, to_move);
+                    line.push(player_move);
+                    to_move = to_move.opposite();
+                }
+                None => break,
+            }
+        }
+        line
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::board;
     use crate::test_common::new_board;
+    use std::time::Duration;
 
     #[test]
     fn it_makes_immediate_win() {
@@ -114,9 +835,15 @@ mod tests {
         board.set_mark(4, Mark::X);
         board.set_mark(6, Mark::X);
         board.set_mark(7, Mark::O);
-        assert_eq!(0, Negamax::default().search(&board, Mark::O));
-        assert_eq!(0, Negamax::search(&board, Mark::O));
-        assert_eq!(0, seq_search(&board, Mark::O));
+        assert_eq!(
+            Ok(Move::Place(0)),
+            Ai::search(&Negamax::default(), &board, Mark::O, &unlimited())
+        );
+        assert_eq!(
+            Move::Place(0),
+            Negamax::default().search(&board, Mark::O, &unlimited())
+        );
+        assert_eq!(Move::Place(0), seq_search(&board, Mark::O));
     }
 
     #[test]
@@ -127,9 +854,15 @@ mod tests {
         board.set_mark(2, Mark::X);
         board.set_mark(4, Mark::O);
         board.set_mark(8, Mark::X);
-        assert_eq!(7, Negamax::default().search(&board, Mark::O));
-        assert_eq!(7, Negamax::search(&board, Mark::O));
-        assert_eq!(7, seq_search(&board, Mark::O));
+        assert_eq!(
+            Ok(Move::Place(7)),
+            Ai::search(&Negamax::default(), &board, Mark::O, &unlimited())
+        );
+        assert_eq!(
+            Move::Place(7),
+            Negamax::default().search(&board, Mark::O, &unlimited())
+        );
+        assert_eq!(Move::Place(7), seq_search(&board, Mark::O));
     }
 
     #[test]
@@ -138,8 +871,11 @@ mod tests {
         board.set_mark(0, Mark::X);
         board.set_mark(2, Mark::X);
         board.set_mark(4, Mark::O);
-        assert_eq!(1, Negamax::search(&board, Mark::O));
-        assert_eq!(1, seq_search(&board, Mark::O));
+        assert_eq!(
+            Move::Place(1),
+            Negamax::default().search(&board, Mark::O, &unlimited())
+        );
+        assert_eq!(Move::Place(1), seq_search(&board, Mark::O));
     }
 
     #[test]
@@ -148,8 +884,20 @@ mod tests {
         board.set_mark(0, Mark::X);
         board.set_mark(4, Mark::O);
         board.set_mark(8, Mark::X);
-        assert_eq!(3, Negamax::search(&board, Mark::O));
-        assert_eq!(3, seq_search(&board, Mark::O));
+        let edges = [1, 3, 5, 7].map(Move::Place);
+        let negamax = Negamax::default();
+        assert!(edges.contains(&negamax.search(&board, Mark::O, &unlimited())));
+        assert!(edges.contains(&seq_search(&board, Mark::O)));
+        let analysis = negamax
+            .analyse(&board, Mark::O, &unlimited())
+            .expect("position is playable");
+        for move_score in &analysis.moves {
+            if edges.contains(&move_score.player_move) {
+                assert_eq!(Outcome::Draw, move_score.outcome);
+            } else {
+                assert!(matches!(move_score.outcome, Outcome::Loss { .. }));
+            }
+        }
     }
 
     #[test]
@@ -158,20 +906,772 @@ mod tests {
         board.set_mark(2, Mark::O);
         board.set_mark(4, Mark::X);
         board.set_mark(6, Mark::X);
-        assert_eq!(0, Negamax::search(&board, Mark::O));
-        assert_eq!(0, seq_search(&board, Mark::O));
+        assert_eq!(
+            Move::Place(0),
+            Negamax::default().search(&board, Mark::O, &unlimited())
+        );
+        assert_eq!(Move::Place(0), seq_search(&board, Mark::O));
     }
 
     #[test]
     fn it_makes_corner_move() {
         let mut board = new_board();
         board.set_mark(0, Mark::X);
-        assert_eq!(4, Negamax::search(&board, Mark::O));
-        assert_eq!(4, seq_search(&board, Mark::O));
+        assert_eq!(
+            Move::Place(4),
+            Negamax::default().search(&board, Mark::O, &unlimited())
+        );
+        assert_eq!(Move::Place(4), seq_search(&board, Mark::O));
+    }
+
+    #[test]
+    fn it_makes_winning_slide() {
+        let board = board!["XXOOOX   ", Variant::Sliding { pieces: 3 }];
+        let expected = Move::Slide { from: 3, to: 6 };
+        assert_eq!(
+            expected,
+            Negamax::default().search(&board, Mark::O, &unlimited())
+        );
+        assert_eq!(expected, seq_search(&board, Mark::O));
+    }
+
+    #[test]
+    fn it_blocks_winning_slide() {
+        let board = board!["XX OOX O ", Variant::Sliding { pieces: 3 }];
+        let expected = Move::Slide { from: 4, to: 2 };
+        assert_eq!(
+            expected,
+            Negamax::default().search(&board, Mark::O, &unlimited())
+        );
+        assert_eq!(expected, seq_search(&board, Mark::O));
+    }
+
+    #[test]
+    fn it_accounts_for_disappearing_marks() {
+        let variant = Variant::Disappearing { marks: 3 };
+        let mut board = Board::with_variant(9, variant);
+        let placements = [
+            (8, Mark::O),
+            (3, Mark::X),
+            (0, Mark::O),
+            (7, Mark::X),
+            (1, Mark::O),
+            (5, Mark::X),
+        ];
+        for &(index, mark) in &placements {
+            board.set_mark(index, mark);
+        }
+        assert_eq!(
+            Move::Place(2),
+            Negamax::default().search(&board, Mark::O, &unlimited())
+        );
+        assert_eq!(Move::Place(2), seq_search(&board, Mark::O));
+    }
+
+    #[test]
+    fn it_plays_around_blocked_cells() {
+        let board = board!["OO#XX    "];
+        assert_eq!(
+            Move::Place(5),
+            Negamax::default().search(&board, Mark::O, &unlimited())
+        );
+        assert_eq!(Move::Place(5), seq_search(&board, Mark::O));
+    }
+
+    #[test]
+    fn it_makes_immediate_win_in_gravity_mode() {
+        let mut board = Board::connect_four();
+        for &index in &[35, 36, 37] {
+            board.set_mark(index, Mark::X);
+        }
+        for &index in &[28, 29, 30] {
+            board.set_mark(index, Mark::O);
+        }
+        assert_eq!(
+            Move::Place(38),
+            Negamax::default().search(&board, Mark::X, &unlimited())
+        );
+    }
+
+    #[test]
+    fn it_blocks_immediate_win_in_gravity_mode() {
+        let mut board = Board::connect_four();
+        for &index in &[38, 31, 24] {
+            board.set_mark(index, Mark::X);
+        }
+        for &index in &[37, 39] {
+            board.set_mark(index, Mark::O);
+        }
+        assert_eq!(
+            Move::Place(17),
+            Negamax::default().search(&board, Mark::O, &unlimited())
+        );
+    }
+
+    #[test]
+    fn it_stores_searched_positions_in_the_table() {
+        let negamax = Negamax::default();
+        assert!(negamax.table.get().is_none());
+        let board = board!["X   O   X"];
+        negamax.search(&board, Mark::O, &unlimited());
+        assert!(table_len(&negamax) > 0);
+    }
+
+    #[test]
+    fn it_clears_the_table_for_a_different_board() {
+        let negamax = Negamax::default();
+        negamax.search(&board!["X   O   X"], Mark::O, &unlimited());
+        assert!(table_len(&negamax) > 0);
+        let larger = Board::with_dimensions(4, 4, 4, Variant::Standard);
+        let shallow = SearchLimits {
+            max_depth: Some(0),
+            ..SearchLimits::default()
+        };
+        negamax.search(&larger, Mark::X, &shallow);
+        assert_eq!(0, table_len(&negamax));
+    }
+
+    #[test]
+    fn it_clears_the_table_for_a_new_game() {
+        let negamax = Negamax::default();
+        let mut game = new_board();
+        game.apply_move(Move::Place(0), Mark::X);
+        game.apply_move(Move::Place(4), Mark::O);
+        negamax.search(&game, Mark::X, &unlimited());
+        let entries = table_len(&negamax);
+        assert!(entries > 0);
+        game.apply_move(Move::Place(8), Mark::X);
+        negamax.search(&game, Mark::O, &unlimited());
+        assert!(table_len(&negamax) >= entries);
+
+        let mut next_game = new_board();
+        next_game.apply_move(Move::Place(4), Mark::X);
+        let shallow = SearchLimits {
+            max_depth: Some(0),
+            ..SearchLimits::default()
+        };
+        negamax.search(&next_game, Mark::O, &shallow);
+        assert_eq!(0, table_len(&negamax));
+    }
+
+    #[test]
+    fn it_does_not_share_scores_in_variants_with_repetition() {
+        let negamax = Negamax::default();
+        let board = board!["XX OO    ", Variant::Sliding { pieces: 3 }];
+        assert_eq!(
+            Move::Place(2),
+            negamax.search(&board, Mark::X, &unlimited())
+        );
+        assert!(negamax.table.get().is_none());
+    }
+
+    #[test]
+    fn it_scores_the_same_with_a_shared_table() {
+        let shared = Negamax::default();
+        let boards = [
+            board!["X        "],
+            board!["X   O   X"],
+            board!["XO  X    "],
+            board!["X   O   X"],
+            board!["X        "],
+        ];
+        let shared_searcher = shared.searcher(&boards[0], &unlimited());
+        for board in &boards {
+            let depth = Negamax::depth(board);
+            let searcher = Negamax::default().searcher(board, &unlimited());
+            let expected = Negamax::seq_search(&searcher, board, Mark::O, depth);
+            assert_eq!(
+                expected,
+                Negamax::seq_search(&shared_searcher, board, Mark::O, depth)
+            );
+        }
+    }
+
+    #[test]
+    fn it_deepens_to_the_end_of_the_game_within_a_node_budget() {
+        let mut board = new_board();
+        board.set_mark(2, Mark::O);
+        board.set_mark(4, Mark::X);
+        board.set_mark(6, Mark::X);
+        let limits = SearchLimits {
+            max_nodes: Some(1_000_000),
+            ..SearchLimits::default()
+        };
+        assert_eq!(
+            Move::Place(0),
+            Negamax::default().search(&board, Mark::O, &limits)
+        );
+    }
+
+    #[test]
+    fn it_falls_back_to_a_legal_move_when_the_budget_runs_out() {
+        let board = board!["X   O   X"];
+        let player_move = Negamax::default().search(&board, Mark::O, &node_limit(1));
+        assert!(board.is_legal_move(player_move, Mark::O));
+    }
+
+    #[test]
+    fn it_returns_the_only_legal_move_without_searching() {
+        let board = board!["XOXOOXXX "];
+        let negamax = Negamax::default();
+        assert_eq!(
+            Move::Place(8),
+            negamax.search(&board, Mark::O, &node_limit(1))
+        );
+        assert_eq!(0, table_len(&negamax));
+    }
+
+    #[test]
+    fn it_makes_immediate_win_on_a_larger_board_within_a_time_limit() {
+        let mut board = Board::with_dimensions(4, 4, 4, Variant::Standard);
+        for &index in &[0, 1, 2] {
+            board.set_mark(index, Mark::O);
+        }
+        for &index in &[5, 9, 10] {
+            board.set_mark(index, Mark::X);
+        }
+        let limits = SearchLimits {
+            move_time: Some(Duration::from_millis(200)),
+            ..SearchLimits::default()
+        };
+        assert_eq!(
+            Move::Place(3),
+            Negamax::default().search(&board, Mark::O, &limits)
+        );
+    }
+
+    #[test]
+    fn it_searches_to_the_given_depth() {
+        let board = board!["X   O   X"];
+        let negamax = Negamax::default();
+        let shallow = SearchLimits {
+            max_depth: Some(0),
+            ..SearchLimits::default()
+        };
+        negamax.search(&board, Mark::O, &shallow);
+        assert_eq!(0, table_len(&negamax));
+        let deep = SearchLimits {
+            max_depth: Some(1),
+            ..SearchLimits::default()
+        };
+        negamax.search(&board, Mark::O, &deep);
+        assert!(table_len(&negamax) > 0);
+    }
+
+    #[test]
+    fn it_finds_the_same_move_with_any_thread_count() {
+        let mut board = new_board();
+        board.set_mark(2, Mark::O);
+        board.set_mark(4, Mark::X);
+        board.set_mark(6, Mark::X);
+        for &threads in &[1, 2, 3, 64] {
+            let limits = SearchLimits {
+                threads: Some(threads),
+                ..SearchLimits::default()
+            };
+            assert_eq!(
+                Move::Place(0),
+                Negamax::default().search(&board, Mark::O, &limits)
+            );
+        }
+    }
+
+    #[test]
+    fn it_uses_the_given_evaluator_at_the_horizon() {
+        #[derive(Debug)]
+        struct PreferCorner;
+
+        impl Evaluator for PreferCorner {
+            fn evaluate(&self, board: &Board, mark: Mark) -> i16 {
+                match board.mark(8) {
+                    Some(&owner) if owner == mark => 50,
+                    Some(_) => -50,
+                    None => 0,
+                }
+            }
+        }
+
+        let board = new_board();
+        let limits = SearchLimits {
+            max_depth: Some(0),
+            ..SearchLimits::default()
+        };
+        let negamax = Negamax::with_evaluator(PreferCorner);
+        assert_eq!(Move::Place(8), negamax.search(&board, Mark::X, &limits));
+    }
+
+    #[test]
+    fn it_loads_heuristic_weights_from_a_file() {
+        let path =
+            std::env::temp_dir().join(format!("ttt-rs-negamax-weights-{}.txt", std::process::id()));
+        let missing = Negamax::with_weights(&path).expect_err("file does not exist yet");
+        assert_eq!(io::ErrorKind::NotFound, missing.kind());
+
+        Heuristic::default()
+            .save(&path)
+            .expect("weights can be saved");
+        let negamax = Negamax::with_weights(&path).expect("weights can be loaded");
+        std::fs::remove_file(&path).expect("weight file can be removed");
+        let limits = SearchLimits {
+            max_depth: Some(0),
+            ..SearchLimits::default()
+        };
+        assert_eq!(
+            Negamax::default().search(&new_board(), Mark::X, &limits),
+            negamax.search(&new_board(), Mark::X, &limits)
+        );
+    }
+
+    #[test]
+    fn it_picks_only_among_the_best_moves_without_mistakes() {
+        let board = board!["OO XX    "];
+        let handicap = Handicap {
+            max_depth: Some(1),
+            mistake_percent: 0,
+            margin: Negamax::MAX,
+        };
+        for seed in 0..10 {
+            let negamax = Negamax::with_handicap(handicap, seed);
+            assert_eq!(
+                Move::Place(2),
+                negamax.search(&board, Mark::O, &unlimited())
+            );
+        }
+    }
+
+    #[test]
+    fn it_varies_among_near_best_moves() {
+        let board = new_board();
+        let handicap = Handicap {
+            max_depth: Some(0),
+            mistake_percent: 0,
+            margin: 2,
+        };
+        let negamax = Negamax::with_handicap(handicap, 7);
+        let moves = (0..20)
+            .map(|_| negamax.search(&board, Mark::X, &unlimited()))
+            .collect::<Vec<Move>>();
+        let near_best = [0, 2, 4, 6, 8].iter().map(|&index| Move::Place(index));
+        assert!(moves
+            .iter()
+            .all(|player_move| near_best.clone().any(|m| m == *player_move)));
+        assert!(moves.iter().any(|&player_move| player_move != moves[0]));
+    }
+
+    #[test]
+    fn it_makes_legal_mistakes() {
+        let board = board!["X   O   X"];
+        let handicap = Handicap {
+            max_depth: Some(1),
+            mistake_percent: 100,
+            margin: 0,
+        };
+        let negamax = Negamax::with_handicap(handicap, 3);
+        for _ in 0..20 {
+            let player_move = negamax.search(&board, Mark::O, &unlimited());
+            assert!(board.is_legal_move(player_move, Mark::O));
+        }
+    }
+
+    #[test]
+    fn it_breaks_ties_from_a_seed() {
+        let board = board!["X   O   X"];
+        let edges = [1, 3, 5, 7].iter().map(|&index| Move::Place(index));
+        let moves = (0..20)
+            .map(|seed| Negamax::with_seed(seed).search(&board, Mark::O, &unlimited()))
+            .collect::<Vec<Move>>();
+        assert!(moves
+            .iter()
+            .all(|player_move| edges.clone().any(|m| m == *player_move)));
+        assert!(moves.iter().any(|&player_move| player_move != moves[0]));
+        for (seed, &player_move) in (0..20).zip(&moves) {
+            let negamax = Negamax::with_seed(seed);
+            assert_eq!(player_move, negamax.search(&board, Mark::O, &unlimited()));
+        }
+    }
+
+    #[test]
+    fn it_breaks_ties_by_move_order_without_a_seed() {
+        let board = board!["X   O   X"];
+        for _ in 0..5 {
+            let player_move = Negamax::default().search(&board, Mark::O, &unlimited());
+            assert_eq!(Move::Place(1), player_move);
+        }
+    }
+
+    #[test]
+    fn it_repeats_handicapped_games_from_a_seed() {
+        let board = new_board();
+        let handicap = Handicap::from(Difficulty::Easy);
+        let first = Negamax::with_handicap(handicap, 11);
+        let second = Negamax::with_handicap(handicap, 11);
+        for _ in 0..5 {
+            assert_eq!(
+                first.search(&board, Mark::X, &unlimited()),
+                second.search(&board, Mark::X, &unlimited())
+            );
+        }
+    }
+
+    #[test]
+    fn it_searches_sequentially_on_request() {
+        let board = board!["X   O   X"];
+        let sequential = Negamax::default().search(&board, Mark::O, &SearchLimits::sequential());
+        assert_eq!(
+            Negamax::default().search(&board, Mark::O, &unlimited()),
+            sequential
+        );
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn it_keeps_its_worker_pool_between_searches() {
+        let negamax = Negamax::default();
+        let limits = SearchLimits {
+            threads: Some(3),
+            ..SearchLimits::default()
+        };
+        let board = board!["X   O   X"];
+        negamax.search(&board, Mark::O, &limits);
+        let pool = negamax.pool(2).expect("helpers are pooled");
+        assert_eq!(2, pool.size());
+        negamax.search(&board, Mark::O, &limits);
+        assert!(Arc::ptr_eq(
+            &pool,
+            &negamax.pool(2).expect("helpers are pooled")
+        ));
+        assert!(negamax.pool(0).is_none());
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn it_defaults_to_the_available_parallelism() {
+        let expected = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        assert_eq!(expected, Negamax::workers(&unlimited()));
+        assert_eq!(1, Negamax::workers(&SearchLimits::sequential()));
+        let limits = SearchLimits {
+            threads: Some(0),
+            ..SearchLimits::default()
+        };
+        assert_eq!(1, Negamax::workers(&limits));
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn it_agrees_with_sequential_scores_using_helper_threads() {
+        let boards = [
+            board!["X        "],
+            board!["XO  X    "],
+            board!["OO XX    "],
+        ];
+        let pool = WorkerPool::new(3);
+        for board in &boards {
+            let negamax = Negamax::default();
+            let searcher = negamax.searcher(board, &unlimited());
+            let expected = Negamax::seq_search(&searcher, board, Mark::O, 4);
+            let smp_searcher = Negamax::default().searcher(board, &unlimited());
+            let scores = Negamax::lazy_smp_search(&smp_searcher, &pool, board, Mark::O, 4);
+            assert_eq!(expected, scores);
+        }
+    }
+
+    #[test]
+    fn it_searches_fewer_nodes_with_move_ordering() {
+        let mut board = Board::with_dimensions(4, 4, 4, Variant::Standard);
+        board.set_mark(5, Mark::X);
+        board.set_mark(10, Mark::O);
+        let limits = SearchLimits {
+            max_depth: Some(4),
+            threads: Some(1),
+            ..SearchLimits::default()
+        };
+        let ordered = Negamax::default();
+        let unordered = Negamax::with_move_ordering(false);
+        assert_eq!(
+            unordered.search(&board, Mark::X, &limits),
+            ordered.search(&board, Mark::X, &limits)
+        );
+        assert!(ordered.nodes() > 0);
+        assert!(ordered.nodes() < unordered.nodes());
+    }
+
+    #[test]
+    fn it_collects_statistics_for_the_last_search() {
+        let negamax = Negamax::default();
+        let board = board!["X   O    "];
+        let moves = board.legal_moves(Mark::X);
+        negamax.search(&board, Mark::X, &SearchLimits::sequential());
+        let stats = Ai::stats(&negamax).expect("negamax keeps statistics");
+        assert_eq!(negamax.nodes(), stats.nodes);
+        assert!(stats.leaf_evaluations > 0);
+        assert!(stats.beta_cutoffs > 0);
+        assert!(stats.table_hits > 0);
+        let timed = stats
+            .root_moves
+            .iter()
+            .map(|&(player_move, _)| player_move)
+            .collect::<Vec<Move>>();
+        assert_eq!(moves, timed);
+        let root_time = stats.root_moves.iter().map(|&(_, elapsed)| elapsed).sum();
+        assert!(stats.elapsed >= root_time);
+    }
+
+    #[test]
+    fn it_includes_statistics_in_the_analysis() {
+        let negamax = Negamax::default();
+        let analysis = negamax
+            .analyse(&board!["X   O    "], Mark::X, &unlimited())
+            .expect("position is playable");
+        assert!(analysis.stats.nodes > 0);
+    }
+
+    #[test]
+    fn it_returns_statistics_with_each_result() {
+        let negamax = Negamax::default();
+        let board = board!["X   O    "];
+        let solution = negamax.solve(&board, Mark::O, &unlimited());
+        assert!(solution.stats.nodes > 0);
+        let result = negamax
+            .search_with_progress(&board, Mark::O, &unlimited(), &CancelToken::new(), |_| {})
+            .expect("board is playable");
+        assert!(result.stats.nodes > 0);
+        assert_eq!(Some(SearchStats::default()), Ai::stats(&negamax));
+    }
+
+    #[test]
+    fn it_analyses_an_immediate_win() {
+        let board = board!["OO XX    "];
+        let analysis = Negamax::default()
+            .analyse(&board, Mark::O, &unlimited())
+            .expect("position is playable");
+        assert_eq!(Move::Place(2), analysis.best_move);
+        assert_eq!(Outcome::Win { plies: 1 }, analysis.outcome);
+        assert_eq!(vec![Move::Place(2)], analysis.principal_variation);
+        assert_eq!(5, analysis.moves.len());
+        let ignored_threat = analysis
+            .moves
+            .iter()
+            .find(|score| score.player_move == Move::Place(6))
+            .expect("every legal move is analysed");
+        assert_eq!(Outcome::Loss { plies: 2 }, ignored_threat.outcome);
+    }
+
+    #[test]
+    fn it_proves_the_empty_board_is_a_draw() {
+        let board = new_board();
+        let limits = SearchLimits {
+            max_depth: Some(9),
+            ..SearchLimits::default()
+        };
+        let analysis = Negamax::default()
+            .analyse(&board, Mark::X, &limits)
+            .expect("position is playable");
+        assert_eq!(Outcome::Draw, analysis.outcome);
+        assert_eq!(0, analysis.score);
+        assert_eq!(9, analysis.principal_variation.len());
+        assert!(analysis
+            .moves
+            .iter()
+            .all(|score| score.outcome == Outcome::Draw));
+    }
+
+    #[test]
+    fn it_reports_unknown_outcomes_past_the_horizon() {
+        let board = Board::with_dimensions(4, 4, 4, Variant::Standard);
+        let limits = SearchLimits {
+            max_depth: Some(2),
+            ..SearchLimits::default()
+        };
+        let analysis = Negamax::default()
+            .analyse(&board, Mark::X, &limits)
+            .expect("position is playable");
+        assert_eq!(Outcome::Unknown, analysis.outcome);
+        assert_eq!(3, analysis.depth);
+        assert_eq!(16, analysis.moves.len());
+    }
+
+    #[test]
+    fn it_analyses_every_move_statically_when_the_budget_runs_out() {
+        let board = board!["OO XX    "];
+        let analysis = Negamax::default()
+            .analyse(&board, Mark::O, &node_limit(1))
+            .expect("position is playable");
+        assert_eq!(0, analysis.depth);
+        assert_eq!(5, analysis.moves.len());
+        assert_eq!(Move::Place(2), analysis.best_move);
+        assert_eq!(Outcome::Win { plies: 1 }, analysis.outcome);
+        assert!(analysis
+            .moves
+            .iter()
+            .filter(|score| score.player_move != Move::Place(2))
+            .all(|score| score.outcome == Outcome::Unknown));
+    }
+
+    #[test]
+    fn it_finds_the_principal_variation_of_a_forced_win() {
+        let board = board!["X   O   X"];
+        let analysis = Negamax::default()
+            .analyse(&board, Mark::X, &unlimited())
+            .expect("position is playable");
+        assert_eq!(Move::Place(2), analysis.best_move);
+        assert_eq!(Outcome::Win { plies: 3 }, analysis.outcome);
+        let line = vec![Move::Place(2), Move::Place(1), Move::Place(5)];
+        assert_eq!(line, analysis.principal_variation);
+        assert!(analysis
+            .moves
+            .iter()
+            .all(|score| matches!(score.outcome, Outcome::Win { .. })));
+    }
+
+    #[test]
+    fn it_refuses_to_move_when_there_is_no_move() {
+        let negamax = Negamax::default();
+        let won = board!["XXXOO    "];
+        assert_eq!(
+            Err(MoveError::GameOver),
+            Ai::search(&negamax, &won, Mark::O, &unlimited())
+        );
+        let full = board!["XOXXOOOXX"];
+        assert_eq!(
+            Err(MoveError::GameOver),
+            Ai::search(&negamax, &full, Mark::O, &unlimited())
+        );
+        assert_eq!(
+            Err(MoveError::GameOver),
+            negamax.analyse(&full, Mark::O, &unlimited())
+        );
+    }
+
+    #[test]
+    fn it_solves_a_forced_win_with_its_distance() {
+        let solution = Negamax::default().solve(&board!["X   O   X"], Mark::X, &unlimited());
+        assert_eq!(Outcome::Win { plies: 3 }, solution.outcome);
+        assert_eq!(Some(Move::Place(2)), solution.best_move);
+        assert!(solution.proven);
+        assert_eq!(3, solution.depth);
+    }
+
+    #[test]
+    fn it_solves_a_forced_loss_with_the_longest_defence() {
+        let solution = Negamax::default().solve(&board!["X X O   X"], Mark::O, &unlimited());
+        assert_eq!(Outcome::Loss { plies: 2 }, solution.outcome);
+        assert!(solution.proven);
+    }
+
+    #[test]
+    fn it_solves_the_empty_board_past_the_default_depth() {
+        let solution = Negamax::default().solve(&new_board(), Mark::X, &unlimited());
+        assert_eq!(Outcome::Draw, solution.outcome);
+        assert!(solution.proven);
+        assert_eq!(9, solution.depth);
+    }
+
+    #[test]
+    fn it_reports_unproven_solutions_when_the_limits_stop_it() {
+        let board = Board::with_dimensions(4, 4, 4, Variant::Standard);
+        let solution = Negamax::default().solve(&board, Mark::X, &node_limit(2_000));
+        assert_eq!(Outcome::Unknown, solution.outcome);
+        assert!(!solution.proven);
+        assert!(solution.best_move.is_some());
+        let shallow = SearchLimits {
+            max_depth: Some(2),
+            ..SearchLimits::default()
+        };
+        assert!(!Negamax::default().solve(&board, Mark::X, &shallow).proven);
+    }
+
+    #[test]
+    fn it_solves_finished_games() {
+        let solution = Negamax::default().solve(&board!["XXXOO    "], Mark::O, &unlimited());
+        assert_eq!(Outcome::Loss { plies: 0 }, solution.outcome);
+        assert_eq!(None, solution.best_move);
+    }
+
+    #[test]
+    fn it_reports_progress_after_each_depth() {
+        let limits = SearchLimits {
+            max_depth: Some(3),
+            ..SearchLimits::default()
+        };
+        let mut reports = vec![];
+        let result = Negamax::default()
+            .search_with_progress(
+                &new_board(),
+                Mark::X,
+                &limits,
+                &CancelToken::new(),
+                |progress| {
+                    reports.push(*progress);
+                },
+            )
+            .expect("board is playable");
+        assert!(new_board().is_legal_move(result.best_move, Mark::X));
+        assert_eq!(
+            reports.last().map(|progress| progress.nodes),
+            Some(result.stats.nodes)
+        );
+        let depths = reports
+            .iter()
+            .map(|progress| progress.depth)
+            .collect::<Vec<usize>>();
+        assert_eq!(vec![1, 2, 3, 4], depths);
+        assert!(reports
+            .windows(2)
+            .all(|pair| pair[0].nodes <= pair[1].nodes));
+        assert!(reports.iter().all(|progress| progress.best_move.is_some()));
+    }
+
+    #[test]
+    fn it_stops_when_cancelled_from_the_progress_callback() {
+        let board = Board::with_dimensions(4, 4, 4, Variant::Standard);
+        let cancel = CancelToken::new();
+        let mut reports = 0;
+        let result = Negamax::default()
+            .search_with_progress(&board, Mark::X, &unlimited(), &cancel, |_| {
+                reports += 1;
+                cancel.cancel();
+            })
+            .expect("board is playable");
+        assert_eq!(1, reports);
+        assert!(board.is_legal_move(result.best_move, Mark::X));
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn it_cancels_a_search_running_in_the_background() {
+        let board = Board::with_dimensions(5, 5, 4, Variant::Standard);
+        let limits = SearchLimits {
+            max_depth: Some(usize::MAX),
+            ..SearchLimits::default()
+        };
+        let negamax = Arc::new(Negamax::default());
+        let handle = negamax.spawn_search(&board, Mark::X, limits, |_| {});
+        handle.cancel();
+        let result = handle.join().expect("board is playable");
+        assert!(board.is_legal_move(result.best_move, Mark::X));
+        let finished = negamax.spawn_search(&board!["XXXOO    "], Mark::O, limits, |_| {});
+        assert_eq!(Err(MoveError::GameOver), finished.join());
+    }
+
+    fn table_len(negamax: &Negamax) -> usize {
+        negamax.table.get().map_or(0, |table| table.len())
+    }
+
+    fn unlimited() -> SearchLimits {
+        SearchLimits::default()
+    }
+
+    fn node_limit(max_nodes: u64) -> SearchLimits {
+        SearchLimits {
+            max_nodes: Some(max_nodes),
+            ..SearchLimits::default()
+        }
     }
 
-    fn seq_search(board: &Board, mark: Mark) -> usize {
-        let mut scores = Negamax::seq_search(board, mark);
+    fn seq_search(board: &Board, mark: Mark) -> Move {
+        let searcher = Negamax::default().searcher(board, &unlimited());
+        let depth = Negamax::depth(board);
+        let mut scores = Negamax::seq_search(&searcher, board, mark, depth);
         scores.sort_by(|a, b| b.1.cmp(&a.1));
         scores[0].0
     }
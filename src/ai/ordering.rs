@@ -0,0 +1,144 @@
+use crate::{Board, Mark, Move};
+use core::cmp::Reverse;
+use core::convert::TryFrom;
+use std::collections::HashMap;
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct MoveOrdering {
+    centrality: Vec<u32>,
+    killers: Vec<[Option<Move>; 2]>,
+    history: HashMap<Move, u32>,
+}
+
+impl MoveOrdering {
+    const TABLE_MOVE: u32 = 1 << 30;
+    const WIN: u32 = 1 << 29;
+    const BLOCK: u32 = 1 << 28;
+    const KILLER: u32 = 1 << 27;
+    const HISTORY_LIMIT: u32 = (1 << 26) - 1;
+
+    pub fn new(board: &Board) -> Self {
+        let mut centrality = vec![0; board.size()];
+        for combo in board.win_combos() {
+            for &index in combo {
+                centrality[index] += 1;
+            }
+        }
+        Self {
+            centrality,
+            ..Self::default()
+        }
+    }
+
+    pub fn order(
+        &self,
+        board: &Board,
+        mark: Mark,
+        moves: &mut [Move],
+        depth: i16,
+        table_move: Option<Move>,
+    ) {
+        let killers = self.killers(depth);
+        moves.sort_by_cached_key(|&player_move| {
+            let priority = if Some(player_move) == table_move {
+                Self::TABLE_MOVE
+            } else if board.is_winning_move(player_move, mark) {
+                Self::WIN
+            } else if board.is_winning_move(Move::Place(Self::target(player_move)), mark.opposite())
+            {
+                Self::BLOCK
+            } else if killers.contains(&Some(player_move)) {
+                Self::KILLER
+            } else {
+                0
+            };
+            Reverse(priority + self.history_score(player_move) + self.static_score(player_move))
+        });
+    }
+
+    pub fn record_cutoff(&mut self, player_move: Move, depth: i16) {
+        let slot = Self::slot(depth);
+        if self.killers.len() <= slot {
+            self.killers.resize(slot + 1, [None, None]);
+        }
+        let killers = &mut self.killers[slot];
+        if killers[0] != Some(player_move) {
+            killers[1] = killers[0];
+            killers[0] = Some(player_move);
+        }
+        let bonus = Self::slot(depth).pow(2);
+        let entry = self.history.entry(player_move).or_insert(0);
+        *entry = entry
+            .saturating_add(u32::try_from(bonus).unwrap_or(u32::MAX))
+            .min(Self::HISTORY_LIMIT);
+    }
+
+    fn killers(&self, depth: i16) -> [Option<Move>; 2] {
+        self.killers
+            .get(Self::slot(depth))
+            .copied()
+            .unwrap_or([None, None])
+    }
+
+    fn history_score(&self, player_move: Move) -> u32 {
+        self.history.get(&player_move).copied().unwrap_or(0)
+    }
+
+    fn static_score(&self, player_move: Move) -> u32 {
+        self.centrality
+            .get(Self::target(player_move))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    fn target(player_move: Move) -> usize {
+        match player_move {
+            Move::Place(index) | Move::Slide { to: index, .. } => index,
+        }
+    }
+
+    fn slot(depth: i16) -> usize {
+        usize::try_from(depth).unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{board, Variant};
+
+    fn places(indices: &[usize]) -> Vec<Move> {
+        indices.iter().map(|&index| Move::Place(index)).collect()
+    }
+
+    #[test]
+    fn it_prefers_the_center_then_corners() {
+        let board = board!["         "];
+        let ordering = MoveOrdering::new(&board);
+        let mut moves = places(&[0, 1, 2, 3, 4, 5, 6, 7, 8]);
+        ordering.order(&board, Mark::X, &mut moves, 3, None);
+        assert_eq!(places(&[4, 0, 2, 6, 8, 1, 3, 5, 7]), moves);
+    }
+
+    #[test]
+    fn it_puts_the_table_move_first_then_wins_then_blocks() {
+        let board = board!["XX OO    "];
+        let ordering = MoveOrdering::new(&board);
+        let mut moves = places(&[2, 5, 6, 7, 8]);
+        ordering.order(&board, Mark::O, &mut moves, 3, Some(Move::Place(7)));
+        assert_eq!(places(&[7, 5, 2, 6, 8]), moves);
+    }
+
+    #[test]
+    fn it_tries_killer_moves_and_history_early() {
+        let board = board!["         "];
+        let mut ordering = MoveOrdering::new(&board);
+        ordering.record_cutoff(Move::Place(7), 3);
+        let mut moves = places(&[0, 4, 7]);
+        ordering.order(&board, Mark::X, &mut moves, 3, None);
+        assert_eq!(places(&[7, 4, 0]), moves);
+        let mut shallow_moves = places(&[4, 7]);
+        ordering.order(&board, Mark::X, &mut shallow_moves, 1, None);
+        assert_eq!(places(&[7, 4]), shallow_moves);
+    }
+}
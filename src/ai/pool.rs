@@ -0,0 +1,106 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+#[derive(Debug)]
+pub(crate) struct WorkerPool {
+    sender: Option<Mutex<Sender<Job>>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl WorkerPool {
+    pub fn new(size: usize) -> Self {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let shared = Arc::new(Mutex::new(receiver));
+        let workers = (0..size)
+            .map(|_| {
+                let jobs = Arc::clone(&shared);
+                thread::spawn(move || Self::work(&jobs))
+            })
+            .collect();
+        Self {
+            sender: Some(Mutex::new(sender)),
+            workers,
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.workers.len()
+    }
+
+    pub fn execute<F>(&self, job: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.sender
+            .as_ref()
+            .expect("pool is running")
+            .lock()
+            .expect("pool lock is poisoned")
+            .send(Box::new(job))
+            .expect("pool workers are running");
+    }
+
+    fn work(jobs: &Mutex<Receiver<Job>>) {
+        loop {
+            let next = jobs.lock().expect("pool lock is poisoned").recv();
+            match next {
+                Ok(job) => job(),
+                Err(_) => break,
+            }
+        }
+    }
+}
+
+impl Drop for WorkerPool {
+    fn drop(&mut self) {
+        drop(self.sender.take());
+        for worker in self.workers.drain(..) {
+            worker.join().expect("pool worker panicked");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn it_runs_jobs_on_a_fixed_number_of_threads() {
+        let pool = WorkerPool::new(3);
+        assert_eq!(3, pool.size());
+        let count = Arc::new(AtomicUsize::new(0));
+        let (done, finished) = mpsc::channel();
+        for _ in 0..10 {
+            let counter = Arc::clone(&count);
+            let sender = done.clone();
+            pool.execute(move || {
+                counter.fetch_add(1, Ordering::Relaxed);
+                sender.send(()).expect("test is waiting");
+            });
+        }
+        for _ in 0..10 {
+            finished.recv().expect("job finished");
+        }
+        assert_eq!(10, count.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn it_reuses_its_threads() {
+        let pool = WorkerPool::new(1);
+        let (done, finished) = mpsc::channel();
+        for _ in 0..2 {
+            let sender = done.clone();
+            pool.execute(move || {
+                sender
+                    .send(thread::current().id())
+                    .expect("test is waiting");
+            });
+        }
+        let first = finished.recv().expect("job finished");
+        assert_eq!(first, finished.recv().expect("job finished"));
+    }
+}
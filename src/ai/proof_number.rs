@@ -0,0 +1,452 @@
+use crate::ai::budget::Budget;
+use crate::{
+    Board, Mark, Move, Negamax, Outcome, Progress, SearchLimits, SearchStats, Solution, Solver,
+};
+use std::time::Instant;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ProofNumber {
+    max_tree_nodes: usize,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Proof {
+    Proven,
+    Disproven,
+    Unknown,
+}
+
+#[derive(Clone, Debug)]
+struct Node {
+    player_move: Option<Move>,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    proof: u32,
+    disproof: u32,
+    attacker_to_move: bool,
+    expanded: bool,
+    ply: usize,
+}
+
+impl ProofNumber {
+    const DEFAULT_MAX_TREE_NODES: usize = 2_000_000;
+    const INFINITY: u32 = u32::MAX;
+    const REPORT_INTERVAL: u64 = 1024;
+
+    pub fn new(max_tree_nodes: usize) -> Self {
+        Self { max_tree_nodes }
+    }
+
+    pub fn solve_with_progress<F>(
+        &self,
+        board: &Board,
+        mark: Mark,
+        limits: &SearchLimits,
+        mut on_progress: F,
+    ) -> Solution
+    where
+        F: FnMut(&Progress),
+    {
+        if let Some(solution) = Solution::terminal(board, mark) {
+            return solution;
+        }
+        let budget = Budget::new(limits);
+        let mut search = Search::new(board, mark, &budget, self.max_tree_nodes);
+        let win = search.prove_shortest(mark, &mut on_progress);
+        if win == Proof::Proven {
+            return search.solution(Outcome::Win {
+                plies: search.plies(0),
+            });
+        }
+        let mut defence = Search::new(board, mark, &budget, self.max_tree_nodes);
+        let loss = defence.prove_shortest(mark.opposite(), &mut on_progress);
+        match (win, loss) {
+            (_, Proof::Proven) => defence.solution(Outcome::Loss {
+                plies: defence.plies(0),
+            }),
+            (Proof::Disproven, Proof::Disproven) => defence.solution(Outcome::Draw),
+            (
+                Proof::Proven | Proof::Disproven | Proof::Unknown,
+                Proof::Disproven | Proof::Unknown,
+            ) => search.solution(Outcome::Unknown),
+        }
+    }
+}
+
+impl Default for ProofNumber {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_MAX_TREE_NODES)
+    }
+}
+
+impl Solver for ProofNumber {
+    fn solve(&self, board: &Board, mark: Mark, limits: &SearchLimits) -> Solution {
+        self.solve_with_progress(board, mark, limits, |_| {})
+    }
+}
+
+struct Search<'a> {
+    board: Board,
+    mark: Mark,
+    attacker: Mark,
+    nodes: Vec<Node>,
+    budget: &'a Budget,
+    max_tree_nodes: usize,
+    max_ply: usize,
+    depth: usize,
+    start: Instant,
+}
+
+impl<'a> Search<'a> {
+    fn new(board: &Board, mark: Mark, budget: &'a Budget, max_tree_nodes: usize) -> Self {
+        Self {
+            board: board.clone(),
+            mark,
+            attacker: mark,
+            nodes: vec![],
+            budget,
+            max_tree_nodes,
+            max_ply: usize::MAX,
+            depth: 0,
+            start: Instant::now(),
+        }
+    }
+
+    fn prove_shortest(&mut self, attacker: Mark, on_progress: &mut dyn FnMut(&Progress)) -> Proof {
+        let proof = self.prove(attacker, on_progress);
+        if proof != Proof::Proven {
+            return proof;
+        }
+        // The first proof tree is whichever one PNS happened to find, so retry
+        // with growing ply bounds until the shortest forced win is proven.
+        let longest = self.plies(0);
+        let proven = self.nodes.clone();
+        for max_ply in 1..longest {
+            self.max_ply = max_ply;
+            if self.prove(attacker, on_progress) != Proof::Disproven {
+                break;
+            }
+        }
+        if self.nodes[0].proof != 0 {
+            self.nodes = proven;
+        }
+        self.max_ply = usize::MAX;
+        Proof::Proven
+    }
+
+    fn prove(&mut self, attacker: Mark, on_progress: &mut dyn FnMut(&Progress)) -> Proof {
+        self.attacker = attacker;
+        self.nodes = vec![Node {
+            player_move: None,
+            parent: None,
+            children: vec![],
+            proof: 1,
+            disproof: 1,
+            attacker_to_move: self.mark == attacker,
+            expanded: false,
+            ply: 0,
+        }];
+        while self.nodes[0].proof != 0 && self.nodes[0].disproof != 0 {
+            if self.nodes.len() >= self.max_tree_nodes || !self.budget.tick() {
+                break;
+            }
+            let (node, path) = self.most_proving();
+            self.expand(node);
+            for _ in 0..path {
+                self.board.undo_move();
+            }
+            self.update(node);
+            if self.budget.nodes() & (ProofNumber::REPORT_INTERVAL - 1) == 0 {
+                on_progress(&self.progress());
+            }
+        }
+        on_progress(&self.progress());
+        match (self.nodes[0].proof, self.nodes[0].disproof) {
+            (0, _) => Proof::Proven,
+            (_, 0) => Proof::Disproven,
+            _ => Proof::Unknown,
+        }
+    }
+
+    fn most_proving(&mut self) -> (usize, usize) {
+        let mut node = 0;
+        let mut path = 0;
+        while self.nodes[node].expanded {
+            let current = &self.nodes[node];
+            let next = current
+                .children
+                .iter()
+                .copied()
+                .find(|&child| {
+                    if current.attacker_to_move {
+                        self.nodes[child].proof == current.proof
+                    } else {
+                        self.nodes[child].disproof == current.disproof
+                    }
+                })
+                .expect("an expanded node has a child on the most proving path");
+            let player_move = self.nodes[next]
+                .player_move
+                .expect("every child has a move");
+            self.board.apply_move(player_move, self.to_move(node));
+            path += 1;
+            node = next;
+        }
+        (node, path)
+    }
+
+    fn expand(&mut self, node: usize) {
+        let to_move = self.to_move(node);
+        let ply = self.nodes[node].ply + 1;
+        self.depth = self.depth.max(ply);
+        let attacker_to_move = !self.nodes[node].attacker_to_move;
+        for player_move in self.board.legal_moves(to_move) {
+            self.board.apply_move(player_move, to_move);
+            let (proof, disproof) = match self.evaluate(to_move.opposite()) {
+                (1, 1) if ply >= self.max_ply => (ProofNumber::INFINITY, 0),
+                numbers => numbers,
+            };
+            self.board.undo_move();
+            let child = self.nodes.len();
+            self.nodes.push(Node {
+                player_move: Some(player_move),
+                parent: Some(node),
+                children: vec![],
+                proof,
+                disproof,
+                attacker_to_move,
+                expanded: false,
+                ply,
+            });
+            self.nodes[node].children.push(child);
+        }
+        self.nodes[node].expanded = true;
+    }
+
+    fn evaluate(&self, to_move: Mark) -> (u32, u32) {
+        let winner = match self.board.winner() {
+            Some(&winner) => Some(winner),
+            None if self.board.is_game_over() => None,
+            None if self.board.legal_moves(to_move).is_empty() => Some(to_move.opposite()),
+            None => return (1, 1),
+        };
+        if winner == Some(self.attacker) {
+            (0, ProofNumber::INFINITY)
+        } else {
+            (ProofNumber::INFINITY, 0)
+        }
+    }
+
+    fn update(&mut self, from: usize) {
+        let mut next = Some(from);
+        while let Some(node) = next {
+            if self.nodes[node].expanded {
+                let children = &self.nodes[node].children;
+                let proofs = children.iter().map(|&child| self.nodes[child].proof);
+                let disproofs = children.iter().map(|&child| self.nodes[child].disproof);
+                let (proof, disproof) = if self.nodes[node].attacker_to_move {
+                    (
+                        proofs.min().unwrap_or(ProofNumber::INFINITY),
+                        Self::sum(disproofs),
+                    )
+                } else {
+                    (
+                        Self::sum(proofs),
+                        disproofs.min().unwrap_or(ProofNumber::INFINITY),
+                    )
+                };
+                self.nodes[node].proof = proof;
+                self.nodes[node].disproof = disproof;
+            }
+            next = self.nodes[node].parent;
+        }
+    }
+
+    fn plies(&self, node: usize) -> usize {
+        let current = &self.nodes[node];
+        let proven = current
+            .children
+            .iter()
+            .copied()
+            .filter(|&child| self.nodes[child].proof == 0)
+            .map(|child| self.plies(child) + 1);
+        if current.children.is_empty() {
+            0
+        } else if current.attacker_to_move {
+            proven.min().unwrap_or(0)
+        } else {
+            proven.max().unwrap_or(0)
+        }
+    }
+
+    fn best_move(&self, outcome: Outcome) -> Option<Move> {
+        let mut children = self.nodes[0].children.iter().copied();
+        let best = match outcome {
+            Outcome::Win { .. } => children
+                .filter(|&child| self.nodes[child].proof == 0)
+                .min_by_key(|&child| self.plies(child)),
+            Outcome::Loss { .. } => children.max_by_key(|&child| self.plies(child)),
+            Outcome::Draw => children.find(|&child| self.nodes[child].disproof == 0),
+            Outcome::Unknown => children.min_by_key(|&child| self.nodes[child].proof),
+        };
+        best.and_then(|child| self.nodes[child].player_move)
+            .or_else(|| self.board.legal_moves(self.mark).first().copied())
+    }
+
+    fn solution(&self, outcome: Outcome) -> Solution {
+        Solution {
+            outcome,
+            best_move: self.best_move(outcome),
+            score: Negamax::score_of(outcome),
+            proven: outcome != Outcome::Unknown,
+            depth: self.depth,
+            stats: SearchStats {
+                nodes: self.budget.nodes(),
+                elapsed: self.start.elapsed(),
+                ..SearchStats::default()
+            },
+        }
+    }
+
+    fn progress(&self) -> Progress {
+        Progress {
+            depth: self.depth,
+            nodes: self.budget.nodes(),
+            best_move: self.best_move(Outcome::Unknown),
+            elapsed: self.start.elapsed(),
+        }
+    }
+
+    fn to_move(&self, node: usize) -> Mark {
+        if self.nodes[node].attacker_to_move {
+            self.attacker
+        } else {
+            self.attacker.opposite()
+        }
+    }
+
+    fn sum(numbers: impl Iterator<Item = u32>) -> u32 {
+        numbers.fold(0, u32::saturating_add)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board;
+    use crate::test_common::new_board;
+    use crate::{Negamax, Tablebase, Variant};
+    use core::mem;
+
+    #[test]
+    fn it_proves_a_forced_win() {
+        let board = board!["X   O   X"];
+        let solution = ProofNumber::default().solve(&board, Mark::X, &unlimited());
+        assert!(matches!(solution.outcome, Outcome::Win { .. }));
+        assert!(solution.proven);
+        let mut after = board.clone();
+        after.apply_move(solution.best_move.expect("a win has a move"), Mark::X);
+        let reply = Negamax::default().solve(&after, Mark::O, &unlimited());
+        assert!(matches!(reply.outcome, Outcome::Loss { .. }));
+    }
+
+    #[test]
+    fn it_finds_the_shortest_forced_win() {
+        let board = Board::with_dimensions(4, 4, 3, Variant::Standard);
+        let solution = ProofNumber::default().solve(&board, Mark::X, &unlimited());
+        let expected = Negamax::default().solve(&board, Mark::X, &unlimited());
+        assert_eq!(Outcome::Win { plies: 5 }, expected.outcome);
+        assert_eq!(expected.outcome, solution.outcome);
+        let mut after = board.clone();
+        after.apply_move(solution.best_move.expect("a win has a move"), Mark::X);
+        let reply = Negamax::default().solve(&after, Mark::O, &unlimited());
+        assert_eq!(Outcome::Loss { plies: 4 }, reply.outcome);
+    }
+
+    #[test]
+    fn it_proves_a_forced_loss() {
+        let board = board!["X X O   X"];
+        let solution = ProofNumber::default().solve(&board, Mark::O, &unlimited());
+        assert_eq!(Outcome::Loss { plies: 2 }, solution.outcome);
+        assert!(solution.proven);
+    }
+
+    #[test]
+    fn it_proves_the_empty_board_is_a_draw() {
+        let solution = ProofNumber::default().solve(&new_board(), Mark::X, &unlimited());
+        assert_eq!(Outcome::Draw, solution.outcome);
+        assert!(solution.proven);
+        assert_eq!(9, solution.depth);
+    }
+
+    #[test]
+    fn it_proves_a_win_on_a_small_gravity_board() {
+        let board = Board::with_dimensions(4, 4, 3, Variant::Gravity);
+        let solution = ProofNumber::default().solve(&board, Mark::X, &unlimited());
+        assert!(matches!(solution.outcome, Outcome::Win { .. }));
+        let best_move = solution.best_move.expect("a win has a move");
+        assert!(board.is_legal_move(best_move, Mark::X));
+    }
+
+    #[test]
+    fn it_agrees_with_the_tablebase_after_the_opening() {
+        let proof_number = ProofNumber::default();
+        for &x_cell in &[0, 1, 4] {
+            for o_cell in (0..9).filter(|&o_cell| o_cell != x_cell) {
+                let mut board = new_board();
+                board.set_mark(x_cell, Mark::X);
+                board.set_mark(o_cell, Mark::O);
+                let entry = Tablebase::lookup(&board, Mark::X).expect("position is known");
+                let solution = proof_number.solve(&board, Mark::X, &unlimited());
+                assert_eq!(
+                    mem::discriminant(&entry.outcome),
+                    mem::discriminant(&solution.outcome)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn it_gives_up_at_the_memory_limit() {
+        let board = Board::with_dimensions(4, 4, 4, Variant::Standard);
+        let solution = ProofNumber::new(50).solve(&board, Mark::X, &unlimited());
+        assert_eq!(Outcome::Unknown, solution.outcome);
+        assert!(!solution.proven);
+        assert!(solution.best_move.is_some());
+    }
+
+    #[test]
+    fn it_gives_up_at_the_node_limit() {
+        let board = Board::with_dimensions(4, 4, 4, Variant::Standard);
+        let limits = SearchLimits {
+            max_nodes: Some(10),
+            ..SearchLimits::default()
+        };
+        let solution = ProofNumber::default().solve(&board, Mark::X, &limits);
+        assert!(!solution.proven);
+    }
+
+    #[test]
+    fn it_reports_progress() {
+        let board = Board::with_dimensions(4, 4, 3, Variant::Standard);
+        let mut reports = vec![];
+        ProofNumber::default().solve_with_progress(&board, Mark::X, &unlimited(), |progress| {
+            reports.push(*progress);
+        });
+        assert!(reports.len() > 1);
+        assert!(reports
+            .windows(2)
+            .all(|pair| pair[0].nodes <= pair[1].nodes));
+        assert!(reports.iter().all(|progress| progress.best_move.is_some()));
+    }
+
+    #[test]
+    fn it_solves_finished_games() {
+        let solution = ProofNumber::default().solve(&board!["XXXOO    "], Mark::O, &unlimited());
+        assert_eq!(Outcome::Loss { plies: 0 }, solution.outcome);
+    }
+
+    fn unlimited() -> SearchLimits {
+        SearchLimits::default()
+    }
+}
@@ -0,0 +1,240 @@
+use crate::ai::learning::{self, Table};
+use crate::ai::rng::Rng;
+use crate::{Ai, Board, Mark, Move, MoveError, Negamax, SearchLimits};
+use std::io;
+use std::mem;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+#[derive(Debug)]
+pub struct QLearning {
+    values: Mutex<Table<f64>>,
+    episode: Mutex<Vec<(u64, Move)>>,
+    rng: Rng,
+    learning_rate: f64,
+    discount: f64,
+    exploration_percent: u8,
+}
+
+impl QLearning {
+    const LEARNING_RATE: f64 = 0.5;
+    const DISCOUNT: f64 = 0.9;
+    const EXPLORATION_PERCENT: u8 = 10;
+
+    pub fn new(learning_rate: f64, discount: f64, exploration_percent: u8, seed: u64) -> Self {
+        Self::with_rng(learning_rate, discount, exploration_percent, Rng::new(seed))
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::new(
+            Self::LEARNING_RATE,
+            Self::DISCOUNT,
+            Self::EXPLORATION_PERCENT,
+            seed,
+        )
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let agent = Self::default();
+        *agent.values() = learning::load(path.as_ref())?;
+        Ok(agent)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        learning::save(&self.values(), path.as_ref())
+    }
+
+    pub fn positions(&self) -> usize {
+        self.values().len()
+    }
+
+    pub fn value(&self, board: &Board, mark: Mark, player_move: Move) -> f64 {
+        self.values()
+            .get(&Negamax::table_key(board, mark))
+            .and_then(|values| values.iter().find(|&&(known, _)| known == player_move))
+            .map_or(0.0, |&(_, value)| value)
+    }
+
+    fn with_rng(learning_rate: f64, discount: f64, exploration_percent: u8, rng: Rng) -> Self {
+        Self {
+            values: Mutex::new(Table::new()),
+            episode: Mutex::new(vec![]),
+            rng,
+            learning_rate,
+            discount,
+            exploration_percent,
+        }
+    }
+
+    fn values(&self) -> MutexGuard<'_, Table<f64>> {
+        self.values.lock().expect("values are not poisoned")
+    }
+
+    fn choose(&self, board: &Board, mark: Mark, moves: &[Move]) -> Move {
+        let key = Negamax::table_key(board, mark);
+        let mut values = self.values();
+        let known = values.entry(key).or_default();
+        for &player_move in moves {
+            if !known
+                .iter()
+                .any(|&(value_move, _)| value_move == player_move)
+            {
+                known.push((player_move, 0.0));
+            }
+        }
+
+        let mut candidates = known
+            .iter()
+            .filter(|(player_move, _)| moves.contains(player_move))
+            .copied()
+            .collect::<Vec<(Move, f64)>>();
+        let offset = self.rng.below(candidates.len());
+        candidates.rotate_left(offset);
+        let chosen = if self.rng.chance(self.exploration_percent) {
+            candidates.first()
+        } else {
+            candidates
+                .iter()
+                .max_by(|(_, first), (_, second)| first.total_cmp(second))
+        }
+        .map(|&(player_move, _)| player_move)
+        .expect("there is a legal move");
+        self.episode
+            .lock()
+            .expect("episode is not poisoned")
+            .push((key, chosen));
+        chosen
+    }
+
+    #[allow(clippy::float_arithmetic)]
+    fn learn(&self, episode: &[(u64, Move)], reward: f64) {
+        let mut values = self.values();
+        let mut target = reward;
+        for &(key, player_move) in episode.iter().rev() {
+            let entries = values.entry(key).or_default();
+            if !entries.iter().any(|&(known, _)| known == player_move) {
+                entries.push((player_move, 0.0));
+            }
+            for (known, value) in entries.iter_mut() {
+                if *known == player_move {
+                    *value += self.learning_rate * (target - *value);
+                }
+            }
+            let best = entries
+                .iter()
+                .map(|&(_, value)| value)
+                .fold(f64::NEG_INFINITY, f64::max);
+            target = self.discount * best;
+        }
+    }
+}
+
+impl Default for QLearning {
+    fn default() -> Self {
+        Self::with_rng(
+            Self::LEARNING_RATE,
+            Self::DISCOUNT,
+            Self::EXPLORATION_PERCENT,
+            Rng::default(),
+        )
+    }
+}
+
+impl Ai for QLearning {
+    fn search(&self, board: &Board, mark: Mark, _limits: &SearchLimits) -> Result<Move, MoveError> {
+        let moves = board.playable_moves(mark)?;
+        Ok(self.choose(board, mark, &moves))
+    }
+
+    fn game_over(&self, _board: &Board, mark: Mark, winner: Option<Mark>) {
+        let episode = mem::take(&mut *self.episode.lock().expect("episode is not poisoned"));
+        let reward = match winner {
+            Some(winning_mark) if winning_mark == mark => 1.0,
+            Some(_) => -1.0,
+            None => 0.0,
+        };
+        self.learn(&episode, reward);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_common::new_board;
+    use crate::{board, Variant};
+    use std::env;
+    use std::fs;
+
+    #[test]
+    fn it_plays_legal_moves() {
+        let agent = QLearning::with_seed(1);
+        let board = new_board();
+        let player_move = agent
+            .search(&board, Mark::X, &SearchLimits::default())
+            .expect("board is playable");
+        assert!(board.is_legal_move(player_move, Mark::X));
+        assert_eq!(1, agent.positions());
+    }
+
+    #[test]
+    fn it_learns_to_take_a_winning_move() {
+        let agent = QLearning::new(0.5, 0.9, 50, 7);
+        let board = board!["XX OO    "];
+        for _ in 0..100 {
+            let player_move = agent
+                .search(&board, Mark::X, &SearchLimits::default())
+                .expect("board is playable");
+            let winner = if player_move == Move::Place(2) {
+                Mark::X
+            } else {
+                Mark::O
+            };
+            agent.game_over(&board, Mark::X, Some(winner));
+        }
+
+        let greedy = QLearning::new(0.5, 0.9, 0, 7);
+        *greedy.values() = agent.values().clone();
+        assert_eq!(
+            Ok(Move::Place(2)),
+            greedy.search(&board, Mark::X, &SearchLimits::default())
+        );
+        assert!(agent.value(&board, Mark::X, Move::Place(2)) > 0.9);
+    }
+
+    #[test]
+    fn it_discounts_earlier_moves() {
+        let agent = QLearning::new(1.0, 0.5, 0, 3);
+        let first = board!["         "];
+        let second = board!["X   O    "];
+        agent.learn(
+            &[
+                (Negamax::table_key(&first, Mark::X), Move::Place(0)),
+                (Negamax::table_key(&second, Mark::X), Move::Place(8)),
+            ],
+            1.0,
+        );
+        assert!((agent.value(&second, Mark::X, Move::Place(8)) - 1.0).abs() < f64::EPSILON);
+        assert!((agent.value(&first, Mark::X, Move::Place(0)) - 0.5).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn it_saves_and_loads_its_values() {
+        let path = env::temp_dir().join(format!("ttt-rs-q-learning-{}.txt", std::process::id()));
+        let agent = QLearning::with_seed(5);
+        let board = new_board();
+        let player_move = agent
+            .search(&board, Mark::X, &SearchLimits::default())
+            .expect("board is playable");
+        agent.game_over(&board, Mark::X, Some(Mark::X));
+        agent.save(&path).expect("values can be saved");
+        let loaded = QLearning::load(&path).expect("values can be loaded");
+        fs::remove_file(&path).expect("value file can be removed");
+        assert_eq!(1, loaded.positions());
+        assert!(
+            (loaded.value(&board, Mark::X, player_move)
+                - agent.value(&board, Mark::X, player_move))
+            .abs()
+                < f64::EPSILON
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use crate::U64Ext;
+use core::convert::TryFrom;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+#[derive(Debug)]
+pub(crate) struct Rng {
+    state: AtomicU64,
+}
+
+impl Rng {
+    const GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+    pub fn new(seed: u64) -> Self {
+        Self {
+            state: AtomicU64::new(seed),
+        }
+    }
+
+    pub fn next_u64(&self) -> u64 {
+        self.state
+            .fetch_add(Self::GAMMA, Ordering::Relaxed)
+            .splitmix()
+    }
+
+    #[allow(clippy::modulo_arithmetic)]
+    pub fn below(&self, bound: usize) -> usize {
+        let range = u64::try_from(bound).expect("bound does not fit in 64 bits");
+        usize::try_from(self.next_u64() % range).expect("value is below the bound")
+    }
+
+    pub fn chance(&self, percent: u8) -> bool {
+        self.below(100) < usize::from(percent)
+    }
+}
+
+impl Default for Rng {
+    fn default() -> Self {
+        Self::new(RandomState::new().build_hasher().finish())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_repeats_the_sequence_for_a_seed() {
+        let first = Rng::new(7);
+        let second = Rng::new(7);
+        for _ in 0..10 {
+            assert_eq!(first.next_u64(), second.next_u64());
+        }
+        assert_ne!(Rng::new(7).next_u64(), Rng::new(8).next_u64());
+    }
+
+    #[test]
+    fn it_stays_below_the_bound() {
+        let rng = Rng::new(42);
+        for _ in 0..1_000 {
+            assert!(rng.below(9) < 9);
+        }
+    }
+
+    #[test]
+    fn it_honours_certain_chances() {
+        let rng = Rng::new(42);
+        for _ in 0..100 {
+            assert!(!rng.chance(0));
+            assert!(rng.chance(100));
+        }
+    }
+}
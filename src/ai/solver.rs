@@ -0,0 +1,65 @@
+use crate::ai::Negamax;
+use crate::{Board, Mark, Move, Outcome, SearchLimits, SearchStats};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Solution {
+    pub outcome: Outcome,
+    pub best_move: Option<Move>,
+    pub score: i16,
+    pub proven: bool,
+    pub depth: usize,
+    pub stats: SearchStats,
+}
+
+impl Solution {
+    pub(crate) fn terminal(board: &Board, mark: Mark) -> Option<Self> {
+        let outcome = match board.winner() {
+            Some(&winner) if winner == mark => Outcome::Win { plies: 0 },
+            Some(_) => Outcome::Loss { plies: 0 },
+            None if board.is_game_over() => Outcome::Draw,
+            None if board.legal_moves(mark).is_empty() => Outcome::Loss { plies: 0 },
+            None => return None,
+        };
+        Some(Self {
+            outcome,
+            best_move: None,
+            score: Negamax::score_of(outcome),
+            proven: true,
+            depth: 0,
+            stats: SearchStats::default(),
+        })
+    }
+}
+
+pub trait Solver {
+    fn solve(&self, board: &Board, mark: Mark, limits: &SearchLimits) -> Solution;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board;
+    use crate::test_common::new_board;
+    use crate::Variant;
+
+    #[test]
+    fn it_solves_finished_games_without_searching() {
+        let won = Solution::terminal(&board!["XXXOO    "], Mark::O).expect("game is over");
+        assert_eq!(Outcome::Loss { plies: 0 }, won.outcome);
+        assert_eq!(None, won.best_move);
+        assert!(won.proven);
+        let lost = Solution::terminal(&board!["XXXOO    "], Mark::X).expect("game is over");
+        assert_eq!(Outcome::Win { plies: 0 }, lost.outcome);
+        let full = Solution::terminal(&board!["XOXXOOOXX"], Mark::O).expect("game is over");
+        assert_eq!(Outcome::Draw, full.outcome);
+        let blocked = board!["OXXXOOXO ", Variant::Sliding { pieces: 4 }];
+        let stuck = Solution::terminal(&blocked, Mark::X).expect("game is over");
+        assert_eq!(Outcome::Loss { plies: 0 }, stuck.outcome);
+        assert_eq!(-999, stuck.score);
+    }
+
+    #[test]
+    fn it_leaves_games_in_progress_to_the_solver() {
+        assert_eq!(None, Solution::terminal(&new_board(), Mark::X));
+    }
+}
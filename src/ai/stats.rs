@@ -0,0 +1,75 @@
+use crate::Move;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SearchStats {
+    pub nodes: u64,
+    pub leaf_evaluations: u64,
+    pub beta_cutoffs: u64,
+    pub table_hits: u64,
+    pub elapsed: Duration,
+    pub root_moves: Vec<(Move, Duration)>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Counters {
+    leaf_evaluations: AtomicU64,
+    beta_cutoffs: AtomicU64,
+    table_hits: AtomicU64,
+}
+
+impl Counters {
+    pub fn record_leaf(&self) {
+        self.leaf_evaluations.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_cutoff(&self) {
+        self.beta_cutoffs.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_table_hit(&self) {
+        self.table_hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn stats(
+        &self,
+        nodes: u64,
+        elapsed: Duration,
+        root_moves: Vec<(Move, Duration)>,
+    ) -> SearchStats {
+        SearchStats {
+            nodes,
+            leaf_evaluations: self.leaf_evaluations.load(Ordering::Relaxed),
+            beta_cutoffs: self.beta_cutoffs.load(Ordering::Relaxed),
+            table_hits: self.table_hits.load(Ordering::Relaxed),
+            elapsed,
+            root_moves,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_collects_counters_into_stats() {
+        let counters = Counters::default();
+        counters.record_leaf();
+        counters.record_leaf();
+        counters.record_cutoff();
+        counters.record_table_hit();
+        let root_moves = vec![(Move::Place(4), Duration::from_millis(2))];
+        let stats = counters.stats(10, Duration::from_millis(3), root_moves.clone());
+        let expected = SearchStats {
+            nodes: 10,
+            leaf_evaluations: 2,
+            beta_cutoffs: 1,
+            table_hits: 1,
+            elapsed: Duration::from_millis(3),
+            root_moves,
+        };
+        assert_eq!(expected, stats);
+    }
+}
@@ -0,0 +1,320 @@
+use crate::{
+    Ai, Board, Cell, Hint, Mark, Move, MoveError, Negamax, Outcome, SearchLimits, SearchStats,
+    Solution, Solver, Variant,
+};
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TablebaseEntry {
+    pub outcome: Outcome,
+    pub best_moves: Vec<Move>,
+}
+
+#[derive(Debug, Default)]
+pub struct Tablebase {
+    fallback: Negamax,
+}
+
+type Cells = [Option<Mark>; 9];
+
+impl Tablebase {
+    const LINES: [[usize; 3]; 8] = [
+        [0, 1, 2],
+        [3, 4, 5],
+        [6, 7, 8],
+        [0, 3, 6],
+        [1, 4, 7],
+        [2, 5, 8],
+        [0, 4, 8],
+        [2, 4, 6],
+    ];
+
+    pub fn lookup(board: &Board, mark: Mark) -> Option<&'static TablebaseEntry> {
+        let cells = Self::cells(board)?;
+        Self::table().get(&(Self::code(&cells), mark))
+    }
+
+    pub fn positions() -> usize {
+        Self::table().len()
+    }
+
+    fn table() -> &'static HashMap<(u16, Mark), TablebaseEntry> {
+        static TABLE: OnceLock<HashMap<(u16, Mark), TablebaseEntry>> = OnceLock::new();
+        TABLE.get_or_init(|| {
+            let mut table = HashMap::new();
+            for &mark in &[Mark::X, Mark::O] {
+                Self::solve(&mut [None; 9], mark, &mut table);
+            }
+            table
+        })
+    }
+
+    fn cells(board: &Board) -> Option<Cells> {
+        if board.variant() != Variant::Standard
+            || board.size() != 9
+            || board.win_combos().len() != Self::LINES.len()
+        {
+            return None;
+        }
+        let mut cells = [None; 9];
+        for (index, cell) in board.iter().enumerate() {
+            cells[index] = match *cell {
+                Cell::Empty => None,
+                Cell::Marked(mark) => Some(mark),
+                Cell::Blocked => return None,
+            };
+        }
+        Some(cells)
+    }
+
+    fn code(cells: &Cells) -> u16 {
+        cells.iter().rev().fold(0, |code, cell| {
+            let digit = match cell {
+                None => 0,
+                Some(Mark::X) => 1,
+                Some(Mark::O) => 2,
+            };
+            code * 3 + digit
+        })
+    }
+
+    fn solve(
+        cells: &mut Cells,
+        mark: Mark,
+        table: &mut HashMap<(u16, Mark), TablebaseEntry>,
+    ) -> Outcome {
+        let key = (Self::code(cells), mark);
+        if let Some(entry) = table.get(&key) {
+            return entry.outcome;
+        }
+        let entry = if Self::has_line(cells, mark.opposite()) {
+            TablebaseEntry {
+                outcome: Outcome::Loss { plies: 0 },
+                best_moves: vec![],
+            }
+        } else if cells.iter().all(Option::is_some) {
+            TablebaseEntry {
+                outcome: Outcome::Draw,
+                best_moves: vec![],
+            }
+        } else {
+            let mut outcomes = vec![];
+            for index in 0..cells.len() {
+                if cells[index].is_none() {
+                    cells[index] = Some(mark);
+                    let reply = Self::solve(cells, mark.opposite(), table);
+                    cells[index] = None;
+                    outcomes.push((Move::Place(index), Self::parent_outcome(reply)));
+                }
+            }
+            let best = outcomes
+                .iter()
+                .map(|&(_, outcome)| outcome)
+                .max_by(|&a, &b| Self::compare(a, b))
+                .expect("position has moves");
+            TablebaseEntry {
+                outcome: best,
+                best_moves: outcomes
+                    .into_iter()
+                    .filter(|&(_, outcome)| outcome == best)
+                    .map(|(player_move, _)| player_move)
+                    .collect(),
+            }
+        };
+        let outcome = entry.outcome;
+        table.insert(key, entry);
+        outcome
+    }
+
+    fn has_line(cells: &Cells, mark: Mark) -> bool {
+        Self::LINES
+            .iter()
+            .any(|line| line.iter().all(|&index| cells[index] == Some(mark)))
+    }
+
+    fn parent_outcome(reply: Outcome) -> Outcome {
+        match reply {
+            Outcome::Win { plies } => Outcome::Loss { plies: plies + 1 },
+            Outcome::Loss { plies } => Outcome::Win { plies: plies + 1 },
+            Outcome::Draw | Outcome::Unknown => reply,
+        }
+    }
+
+    fn compare(a: Outcome, b: Outcome) -> Ordering {
+        Self::rank(a).cmp(&Self::rank(b))
+    }
+
+    fn rank(outcome: Outcome) -> (i8, isize) {
+        match outcome {
+            Outcome::Win { plies } => (2, -Self::signed(plies)),
+            Outcome::Draw => (1, 0),
+            Outcome::Unknown => (0, 0),
+            Outcome::Loss { plies } => (-1, Self::signed(plies)),
+        }
+    }
+
+    fn signed(plies: usize) -> isize {
+        isize::try_from(plies).unwrap_or(isize::MAX)
+    }
+}
+
+impl Ai for Tablebase {
+    fn search(&self, board: &Board, mark: Mark, limits: &SearchLimits) -> Result<Move, MoveError> {
+        match Self::lookup(board, mark).and_then(|entry| entry.best_moves.first()) {
+            Some(&player_move) => Ok(player_move),
+            None => self.fallback.search(board, mark, limits),
+        }
+    }
+
+    fn hint(&self, board: &Board, mark: Mark, limits: &SearchLimits) -> Result<Hint, MoveError> {
+        let known = Self::lookup(board, mark).and_then(|entry| {
+            entry.best_moves.first().map(|&player_move| Hint {
+                player_move,
+                score: Some(Negamax::score_of(entry.outcome)),
+                outcome: entry.outcome,
+            })
+        });
+        match known {
+            Some(hint) => Ok(hint),
+            None => self.fallback.hint(board, mark, limits),
+        }
+    }
+}
+
+impl Solver for Tablebase {
+    fn solve(&self, board: &Board, mark: Mark, limits: &SearchLimits) -> Solution {
+        match Self::lookup(board, mark) {
+            Some(entry) => Solution {
+                outcome: entry.outcome,
+                best_move: entry.best_moves.first().copied(),
+                score: Negamax::score_of(entry.outcome),
+                proven: true,
+                depth: match entry.outcome {
+                    Outcome::Win { plies } | Outcome::Loss { plies } => plies,
+                    Outcome::Draw | Outcome::Unknown => board.empty_cell_indices().len(),
+                },
+                stats: SearchStats::default(),
+            },
+            None => self.fallback.solve(board, mark, limits),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board;
+    use crate::test_common::new_board;
+
+    #[test]
+    fn it_covers_every_reachable_position() {
+        let x_to_move = Tablebase::table()
+            .keys()
+            .filter(|&&(_, mark)| mark == Mark::X)
+            .count();
+        assert_eq!(5_478, x_to_move);
+        assert_eq!(2 * 5_478, Tablebase::positions());
+    }
+
+    #[test]
+    fn it_knows_the_empty_board_is_a_draw() {
+        let entry = Tablebase::lookup(&new_board(), Mark::X).expect("empty board is known");
+        assert_eq!(Outcome::Draw, entry.outcome);
+        assert_eq!(9, entry.best_moves.len());
+    }
+
+    #[test]
+    fn it_looks_up_wins_and_losses() {
+        let lookup =
+            |board: &Board, mark| Tablebase::lookup(board, mark).expect("position is known");
+        let win = lookup(&board!["XX OO    "], Mark::X);
+        assert_eq!(Outcome::Win { plies: 1 }, win.outcome);
+        assert_eq!(vec![Move::Place(2)], win.best_moves);
+        let draw = lookup(&board!["X   O   X"], Mark::O);
+        assert_eq!(Outcome::Draw, draw.outcome);
+        let loss = lookup(&board!["XXX OO   "], Mark::O);
+        assert_eq!(Outcome::Loss { plies: 0 }, loss.outcome);
+        assert!(loss.best_moves.is_empty());
+    }
+
+    #[test]
+    fn it_only_knows_standard_3x3_boards() {
+        let short_lines = Board::with_dimensions(3, 3, 2, Variant::Standard);
+        assert_eq!(None, Tablebase::lookup(&short_lines, Mark::X));
+        let sliding = Board::with_variant(9, Variant::Sliding { pieces: 3 });
+        assert_eq!(None, Tablebase::lookup(&sliding, Mark::X));
+        assert_eq!(None, Tablebase::lookup(&board!["#        "], Mark::X));
+        assert_eq!(None, Tablebase::lookup(&board!["XX       "], Mark::X));
+    }
+
+    #[test]
+    fn it_plays_the_best_move_and_falls_back_to_negamax() {
+        let tablebase = Tablebase::default();
+        let limits = SearchLimits::default();
+        let board = board!["OO XX    "];
+        assert_eq!(
+            Ok(Move::Place(2)),
+            tablebase.search(&board, Mark::O, &limits)
+        );
+        let larger = Board::with_dimensions(4, 4, 4, Variant::Standard);
+        let player_move = tablebase
+            .search(&larger, Mark::X, &limits)
+            .expect("position is playable");
+        assert!(larger.is_legal_move(player_move, Mark::X));
+    }
+
+    #[test]
+    fn it_solves_positions_from_the_table() {
+        let tablebase = Tablebase::default();
+        let limits = SearchLimits::default();
+        let solution = tablebase.solve(&board!["X   O   X"], Mark::O, &limits);
+        assert_eq!(Outcome::Draw, solution.outcome);
+        assert!(solution.proven);
+        let larger = Board::with_dimensions(4, 4, 4, Variant::Standard);
+        let shallow = SearchLimits {
+            max_depth: Some(1),
+            ..SearchLimits::default()
+        };
+        assert!(!tablebase.solve(&larger, Mark::X, &shallow).proven);
+    }
+
+    #[test]
+    fn it_reports_scores_like_negamax() {
+        let board = board!["XX OO    "];
+        let limits = SearchLimits::sequential();
+        let expected = Negamax::default().solve(&board, Mark::X, &limits);
+        let solution = Tablebase::default().solve(&board, Mark::X, &limits);
+        assert_eq!(Outcome::Win { plies: 1 }, solution.outcome);
+        assert_eq!(expected.score, solution.score);
+        assert_eq!(expected.depth, solution.depth);
+    }
+
+    #[test]
+    fn it_agrees_with_negamax_near_the_end_of_the_game() {
+        let negamax = Negamax::default();
+        let limits = SearchLimits::sequential();
+        for (&(code, mark), entry) in Tablebase::table() {
+            let mut board = new_board();
+            let mut rest = code;
+            for index in 0..9 {
+                match rest % 3 {
+                    1 => board.set_mark(index, Mark::X),
+                    2 => board.set_mark(index, Mark::O),
+                    _ => None,
+                };
+                rest /= 3;
+            }
+            if board.empty_cell_indices().len() > 5 || entry.best_moves.is_empty() {
+                continue;
+            }
+            let analysis = negamax
+                .analyse(&board, mark, &limits)
+                .expect("position is playable");
+            assert_eq!(entry.outcome, analysis.outcome);
+            assert!(entry.best_moves.contains(&analysis.best_move));
+        }
+    }
+}
@@ -0,0 +1,415 @@
+use crate::ai::budget::Budget;
+use crate::{Ai, Board, Mark, Move, MoveError, Negamax, SearchLimits, Variant};
+use std::collections::{BTreeSet, HashMap};
+use std::time::Instant;
+
+#[derive(Debug)]
+pub struct ThreatSpace<T: Ai = Negamax> {
+    max_threats: usize,
+    fallback: T,
+}
+
+impl<T> ThreatSpace<T>
+where
+    T: Ai,
+{
+    const DEFAULT_MAX_THREATS: usize = 8;
+    const DEFAULT_MAX_NODES: u64 = 200_000;
+    const MIN_WIN_LENGTH: usize = 3;
+
+    pub fn new(fallback: T, max_threats: usize) -> Self {
+        Self {
+            max_threats,
+            fallback,
+        }
+    }
+
+    pub fn with_fallback(fallback: T) -> Self {
+        Self::new(fallback, Self::DEFAULT_MAX_THREATS)
+    }
+
+    pub fn forced_win(&self, board: &Board, mark: Mark, limits: &SearchLimits) -> Option<Move> {
+        self.forced_win_within(board, mark, &Self::budget(limits))
+    }
+
+    pub fn has_forced_win(&self, board: &Board, mark: Mark, limits: &SearchLimits) -> bool {
+        self.forced_win(board, mark, limits).is_some()
+    }
+
+    fn budget(limits: &SearchLimits) -> Budget {
+        Budget::new(&SearchLimits {
+            max_nodes: Some(limits.max_nodes.unwrap_or(Self::DEFAULT_MAX_NODES)),
+            move_time: limits.move_time.map(|move_time| move_time / 2),
+            ..*limits
+        })
+    }
+
+    fn forced_win_within(&self, board: &Board, mark: Mark, budget: &Budget) -> Option<Move> {
+        if board.variant() != Variant::Standard || board.playable_moves(mark).is_err() {
+            return None;
+        }
+        let mut threats = Threats::new(board, budget);
+        if threats.win_length < Self::MIN_WIN_LENGTH {
+            return None;
+        }
+        (1..=self.max_threats)
+            .find_map(|depth| threats.attack(mark, depth))
+            .map(Move::Place)
+    }
+}
+
+impl Default for ThreatSpace<Negamax> {
+    fn default() -> Self {
+        Self::with_fallback(Negamax::default())
+    }
+}
+
+impl<T> Ai for ThreatSpace<T>
+where
+    T: Ai,
+{
+    fn search(&self, board: &Board, mark: Mark, limits: &SearchLimits) -> Result<Move, MoveError> {
+        let start = Instant::now();
+        let budget = Self::budget(limits);
+        if let Some(player_move) = self.forced_win_within(board, mark, &budget) {
+            return Ok(player_move);
+        }
+        let remaining = SearchLimits {
+            max_nodes: limits
+                .max_nodes
+                .map(|max_nodes| max_nodes.saturating_sub(budget.nodes())),
+            move_time: limits
+                .move_time
+                .map(|move_time| move_time.saturating_sub(start.elapsed())),
+            ..*limits
+        };
+        self.fallback.search(board, mark, &remaining)
+    }
+}
+
+struct Threats<'a> {
+    board: Board,
+    combos: Vec<Vec<usize>>,
+    cell_combos: Vec<Vec<usize>>,
+    counts: Vec<[usize; 2]>,
+    dead: Vec<bool>,
+    win_length: usize,
+    budget: &'a Budget,
+    refuted: HashMap<(u64, Mark), usize>,
+}
+
+impl<'a> Threats<'a> {
+    fn new(board: &Board, budget: &'a Budget) -> Self {
+        let combos = board.win_combos().clone();
+        let mut cell_combos = vec![vec![]; board.size()];
+        let mut counts = vec![[0, 0]; combos.len()];
+        let mut dead = vec![false; combos.len()];
+        for (combo_index, combo) in combos.iter().enumerate() {
+            for &index in combo {
+                cell_combos[index].push(combo_index);
+                match board.mark(index) {
+                    Some(&mark) => counts[combo_index][Self::side(mark)] += 1,
+                    None => dead[combo_index] |= board.is_blocked(index),
+                }
+            }
+        }
+        Self {
+            board: board.clone(),
+            win_length: combos.first().map_or(0, Vec::len),
+            combos,
+            cell_combos,
+            counts,
+            dead,
+            budget,
+            refuted: HashMap::new(),
+        }
+    }
+
+    fn attack(&mut self, mark: Mark, depth: usize) -> Option<usize> {
+        if !self.budget.tick() {
+            return None;
+        }
+        if let Some(&winning_cell) = self.winning_cells(mark).iter().next() {
+            return Some(winning_cell);
+        }
+        let key = (self.board.position_key(), mark);
+        if self
+            .refuted
+            .get(&key)
+            .is_some_and(|&refuted| refuted >= depth)
+        {
+            return None;
+        }
+        let defences = self.winning_cells(mark.opposite());
+        let candidates = match defences.iter().next() {
+            _ if depth == 0 || defences.len() > 1 => vec![],
+            Some(&block) => vec![block],
+            None => self.threat_candidates(mark),
+        };
+        for cell in candidates {
+            self.place(cell, mark);
+            let wins = self.is_threat(cell, mark) && self.defend(mark, depth - 1);
+            self.remove(cell, mark);
+            if wins {
+                return Some(cell);
+            }
+        }
+        if !self.budget.is_exhausted() {
+            self.refuted.insert(key, depth);
+        }
+        None
+    }
+
+    fn defend(&mut self, mark: Mark, depth: usize) -> bool {
+        let defender = mark.opposite();
+        if !self.winning_cells(defender).is_empty() {
+            return false;
+        }
+        let wins = self.winning_cells(mark);
+        if wins.len() > 1 {
+            return true;
+        }
+        let replies = if wins.is_empty() {
+            let mut replies = self.cells_in_lines(mark, self.win_length - 2);
+            replies.extend(self.cells_in_lines(defender, self.win_length - 2));
+            replies
+        } else {
+            wins
+        };
+        replies.into_iter().all(|cell| {
+            self.place(cell, defender);
+            let refuted = self.attack(mark, depth).is_some();
+            self.remove(cell, defender);
+            refuted
+        })
+    }
+
+    fn is_threat(&self, cell: usize, mark: Mark) -> bool {
+        let through_cell = &self.cell_combos[cell];
+        if self
+            .open_lines(mark, self.win_length - 1)
+            .any(|combo_index| through_cell.contains(&combo_index))
+        {
+            return true;
+        }
+        let mut completions: HashMap<usize, BTreeSet<(usize, bool)>> = HashMap::new();
+        for combo_index in self.open_lines(mark, self.win_length - 2) {
+            if let [first, second] = self.empty_cells(combo_index)[..] {
+                let created = through_cell.contains(&combo_index);
+                completions
+                    .entry(first)
+                    .or_default()
+                    .insert((second, created));
+                completions
+                    .entry(second)
+                    .or_default()
+                    .insert((first, created));
+            }
+        }
+        completions.values().any(|partners| {
+            let cells = partners
+                .iter()
+                .map(|&(partner, _)| partner)
+                .collect::<BTreeSet<usize>>();
+            cells.len() > 1 && partners.iter().any(|&(_, created)| created)
+        })
+    }
+
+    fn threat_candidates(&self, mark: Mark) -> Vec<usize> {
+        let minimum = self.win_length.saturating_sub(3);
+        self.cells_in_lines(mark, minimum).into_iter().collect()
+    }
+
+    fn winning_cells(&self, mark: Mark) -> BTreeSet<usize> {
+        self.cells_in_lines(mark, self.win_length - 1)
+    }
+
+    fn cells_in_lines(&self, mark: Mark, minimum: usize) -> BTreeSet<usize> {
+        self.open_lines(mark, minimum)
+            .flat_map(|combo_index| self.empty_cells(combo_index))
+            .collect()
+    }
+
+    fn open_lines(&self, mark: Mark, minimum: usize) -> impl Iterator<Item = usize> + '_ {
+        let own = Self::side(mark);
+        let other = Self::side(mark.opposite());
+        (0..self.combos.len()).filter(move |&combo_index| {
+            let counts = self.counts[combo_index];
+            !self.dead[combo_index] && counts[other] == 0 && counts[own] >= minimum
+        })
+    }
+
+    fn empty_cells(&self, combo_index: usize) -> Vec<usize> {
+        self.combos[combo_index]
+            .iter()
+            .copied()
+            .filter(|&index| !self.board.is_occupied(index))
+            .collect()
+    }
+
+    fn place(&mut self, index: usize, mark: Mark) {
+        self.board.set_mark(index, mark);
+        for &combo_index in &self.cell_combos[index] {
+            self.counts[combo_index][Self::side(mark)] += 1;
+        }
+    }
+
+    fn remove(&mut self, index: usize, mark: Mark) {
+        self.board.reset_mark(index);
+        for &combo_index in &self.cell_combos[index] {
+            self.counts[combo_index][Self::side(mark)] -= 1;
+        }
+    }
+
+    fn side(mark: Mark) -> usize {
+        match mark {
+            Mark::O => 0,
+            Mark::X => 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    #[test]
+    fn it_turns_an_open_three_into_an_open_four() {
+        let board = gomoku(&[39, 40, 41], &[0, 80]);
+        let threat_space = ThreatSpace::default();
+        assert_eq!(
+            Some(Move::Place(38)),
+            threat_space.forced_win(&board, Mark::X, &unlimited())
+        );
+        assert!(!threat_space.has_forced_win(&board, Mark::O, &unlimited()));
+    }
+
+    #[test]
+    fn it_combines_two_closed_threes_into_a_double_four() {
+        let board = gomoku(&[38, 39, 40, 14, 23, 32], &[37, 5, 0, 80, 72, 8]);
+        assert_eq!(
+            Some(Move::Place(41)),
+            ThreatSpace::default().forced_win(&board, Mark::X, &unlimited())
+        );
+    }
+
+    #[test]
+    fn it_follows_a_four_with_an_open_four() {
+        let board = gomoku(&[38, 39, 40, 23, 32], &[37, 0, 80]);
+        assert_eq!(
+            Some(Move::Place(41)),
+            ThreatSpace::default().forced_win(&board, Mark::X, &unlimited())
+        );
+        let single_threat = ThreatSpace::new(Negamax::default(), 1);
+        assert!(!single_threat.has_forced_win(&board, Mark::X, &unlimited()));
+    }
+
+    #[test]
+    fn it_finds_no_win_without_threats() {
+        let board = gomoku(&[40, 41], &[0]);
+        assert!(!ThreatSpace::default().has_forced_win(&board, Mark::X, &unlimited()));
+    }
+
+    #[test]
+    fn it_gives_up_the_attack_to_block_a_four() {
+        let board = gomoku(&[39, 40, 41, 1], &[72, 73, 74, 75]);
+        assert!(!ThreatSpace::default().has_forced_win(&board, Mark::X, &unlimited()));
+    }
+
+    #[test]
+    fn it_limits_the_number_of_threats() {
+        let board = gomoku(&[39, 40, 41], &[0, 80]);
+        let threat_space = ThreatSpace::new(Negamax::default(), 0);
+        assert!(!threat_space.has_forced_win(&board, Mark::X, &unlimited()));
+    }
+
+    #[test]
+    fn it_falls_back_to_the_regular_ai() {
+        let threat_space = ThreatSpace::default();
+        let board = gomoku(&[39, 40, 41], &[0, 80]);
+        assert_eq!(
+            Ok(Move::Place(38)),
+            threat_space.search(&board, Mark::X, &unlimited())
+        );
+        let quiet = gomoku(&[40], &[]);
+        let player_move = threat_space
+            .search(
+                &quiet,
+                Mark::O,
+                &SearchLimits {
+                    max_depth: Some(1),
+                    ..SearchLimits::default()
+                },
+            )
+            .expect("board is playable");
+        assert!(quiet.is_legal_move(player_move, Mark::O));
+        let finished = board!["XXXOO    "];
+        assert_eq!(
+            Err(MoveError::GameOver),
+            threat_space.search(&finished, Mark::O, &unlimited())
+        );
+    }
+
+    #[test]
+    fn it_gives_the_fallback_what_is_left_of_the_budget() {
+        let threat_space = ThreatSpace::with_fallback(Recorder::default());
+        let board = gomoku(&[40], &[]);
+        let limits = SearchLimits {
+            max_nodes: Some(1_000),
+            move_time: Some(Duration::from_secs(10)),
+            ..SearchLimits::default()
+        };
+        threat_space
+            .search(&board, Mark::O, &limits)
+            .expect("board is playable");
+        let given = threat_space
+            .fallback
+            .limits
+            .lock()
+            .expect("limits lock is poisoned")
+            .expect("fallback was searched");
+        assert!(given.max_nodes < limits.max_nodes);
+        assert!(given.move_time < limits.move_time);
+    }
+
+    #[test]
+    fn it_only_searches_standard_boards() {
+        let board = Board::with_dimensions(6, 7, 4, Variant::Gravity);
+        assert!(!ThreatSpace::default().has_forced_win(&board, Mark::X, &unlimited()));
+    }
+
+    fn gomoku(x_cells: &[usize], o_cells: &[usize]) -> Board {
+        let mut board = Board::with_dimensions(9, 9, 5, Variant::Standard);
+        for &index in x_cells {
+            board.set_mark(index, Mark::X);
+        }
+        for &index in o_cells {
+            board.set_mark(index, Mark::O);
+        }
+        board
+    }
+
+    fn unlimited() -> SearchLimits {
+        SearchLimits::default()
+    }
+
+    #[derive(Debug, Default)]
+    struct Recorder {
+        limits: Mutex<Option<SearchLimits>>,
+    }
+
+    impl Ai for Recorder {
+        fn search(
+            &self,
+            board: &Board,
+            mark: Mark,
+            limits: &SearchLimits,
+        ) -> Result<Move, MoveError> {
+            *self.limits.lock().expect("limits lock is poisoned") = Some(*limits);
+            board.playable_moves(mark).map(|moves| moves[0])
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use crate::Move;
+use std::sync::Mutex;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Bound {
+    Exact,
+    Lower,
+    Upper,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Entry {
+    pub key: u64,
+    pub depth: i16,
+    pub score: i16,
+    pub bound: Bound,
+    pub best: Option<Move>,
+}
+
+impl Entry {
+    pub fn bound_for(score: i16, alpha: i16, beta: i16) -> Bound {
+        if score <= alpha {
+            Bound::Upper
+        } else if score >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct TranspositionTable {
+    shards: Vec<Mutex<Vec<Option<Entry>>>>,
+    mask: u64,
+}
+
+impl TranspositionTable {
+    const SHARD_BITS: u32 = 6;
+    const DEFAULT_SLOTS: usize = 1 << 18;
+
+    #[allow(clippy::as_conversions)]
+    pub fn new(slots: usize) -> Self {
+        let shard_count = 1 << Self::SHARD_BITS;
+        let total = slots.max(shard_count).next_power_of_two();
+        let shards = (0..shard_count)
+            .map(|_| Mutex::new(vec![None; total >> Self::SHARD_BITS]))
+            .collect();
+        Self {
+            shards,
+            mask: total as u64 - 1,
+        }
+    }
+
+    pub fn probe(&self, key: u64) -> Option<Entry> {
+        let (shard, slot) = self.locate(key);
+        let entries = self.shards[shard].lock().expect("table lock is poisoned");
+        entries[slot].filter(|entry| entry.key == key)
+    }
+
+    pub fn store(&self, entry: Entry) {
+        let (shard, slot) = self.locate(entry.key);
+        let mut entries = self.shards[shard].lock().expect("table lock is poisoned");
+        let replace = match entries[slot] {
+            Some(existing) => existing.key != entry.key || existing.depth <= entry.depth,
+            None => true,
+        };
+        if replace {
+            entries[slot] = Some(entry);
+        }
+    }
+
+    pub fn clear(&self) {
+        for shard in &self.shards {
+            let mut entries = shard.lock().expect("table lock is poisoned");
+            entries.iter_mut().for_each(|entry| *entry = None);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        #![allow(dead_code)]
+        self.shards
+            .iter()
+            .map(|shard| {
+                let entries = shard.lock().expect("table lock is poisoned");
+                entries.iter().filter(|entry| entry.is_some()).count()
+            })
+            .sum()
+    }
+
+    #[allow(clippy::as_conversions, clippy::cast_possible_truncation)]
+    fn locate(&self, key: u64) -> (usize, usize) {
+        let index = (key & self.mask) as usize;
+        let shard_mask = (1 << Self::SHARD_BITS) - 1;
+        (index & shard_mask, index >> Self::SHARD_BITS)
+    }
+}
+
+impl Default for TranspositionTable {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_SLOTS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(key: u64, depth: i16) -> Entry {
+        Entry {
+            key,
+            depth,
+            score: 10,
+            bound: Bound::Exact,
+            best: Some(Move::Place(4)),
+        }
+    }
+
+    #[test]
+    fn it_stores_and_probes_entries() {
+        let table = TranspositionTable::new(128);
+        assert_eq!(None, table.probe(42));
+        table.store(entry(42, 3));
+        assert_eq!(Some(entry(42, 3)), table.probe(42));
+        assert_eq!(1, table.len());
+    }
+
+    #[test]
+    fn it_does_not_return_entries_for_other_keys() {
+        let table = TranspositionTable::new(128);
+        table.store(entry(42, 3));
+        assert_eq!(None, table.probe(42 + 128));
+    }
+
+    #[test]
+    fn it_prefers_deeper_entries_for_the_same_key() {
+        let table = TranspositionTable::new(128);
+        table.store(entry(42, 5));
+        table.store(entry(42, 3));
+        assert_eq!(Some(entry(42, 5)), table.probe(42));
+        table.store(entry(42, 7));
+        assert_eq!(Some(entry(42, 7)), table.probe(42));
+    }
+
+    #[test]
+    fn it_replaces_entries_for_colliding_keys() {
+        let table = TranspositionTable::new(128);
+        table.store(entry(42, 5));
+        table.store(entry(42 + 128, 1));
+        assert_eq!(None, table.probe(42));
+        assert_eq!(Some(entry(42 + 128, 1)), table.probe(42 + 128));
+    }
+
+    #[test]
+    fn it_clears_all_entries() {
+        let table = TranspositionTable::new(128);
+        table.store(entry(42, 3));
+        table.store(entry(7, 1));
+        table.clear();
+        assert_eq!(0, table.len());
+        assert_eq!(None, table.probe(42));
+    }
+
+    #[test]
+    fn it_classifies_bounds_against_the_window() {
+        assert_eq!(Bound::Upper, Entry::bound_for(-5, -5, 5));
+        assert_eq!(Bound::Lower, Entry::bound_for(5, -5, 5));
+        assert_eq!(Bound::Exact, Entry::bound_for(0, -5, 5));
+    }
+}
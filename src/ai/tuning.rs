@@ -0,0 +1,185 @@
+use crate::ai::rng::Rng;
+use crate::{Ai, Board, Heuristic, Mark, MoveError, Negamax, SearchLimits};
+use core::convert::TryFrom;
+
+#[derive(Debug)]
+pub struct Tuner {
+    iterations: usize,
+    games: usize,
+    step: i16,
+    opening_plies: usize,
+    limits: SearchLimits,
+    rng: Rng,
+}
+
+impl Tuner {
+    const ITERATIONS: usize = 20;
+    const GAMES: usize = 8;
+    const STEP: i16 = 2;
+    const OPENING_PLIES: usize = 2;
+    const MAX_WEIGHT: i16 = 200;
+    const MAX_PLIES: usize = 100;
+
+    pub fn new(iterations: usize, games: usize, seed: u64) -> Self {
+        Self::with_rng(iterations, games, Rng::new(seed))
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::new(Self::ITERATIONS, Self::GAMES, seed)
+    }
+
+    // Sign-based SPSA: perturb every weight at once in a random direction,
+    // play the two perturbed sets against each other and step towards the
+    // winner. Match results are too noisy for a useful gradient magnitude.
+    pub fn tune(&self, board: &Board, start: Heuristic) -> Heuristic {
+        let mut weights = start.weights();
+        for _ in 0..self.iterations {
+            let mut plus = weights;
+            let mut minus = weights;
+            let mut directions = [0_i16; 5];
+            for (index, direction) in directions.iter_mut().enumerate() {
+                *direction = if self.rng.chance(50) { 1 } else { -1 };
+                let delta = direction.saturating_mul(self.step);
+                if let (Some(up), Some(down)) = (plus.get_mut(index), minus.get_mut(index)) {
+                    *up = Self::clamp(up.saturating_add(delta));
+                    *down = Self::clamp(down.saturating_sub(delta));
+                }
+            }
+            let score = self.play_match(
+                board,
+                Heuristic::from_weights(plus),
+                Heuristic::from_weights(minus),
+            );
+            let sign = i16::try_from(score.signum()).expect("sign fits in 16 bits");
+            for (weight, direction) in weights.iter_mut().zip(directions.iter()) {
+                let delta = direction.saturating_mul(self.step).saturating_mul(sign);
+                *weight = Self::clamp(weight.saturating_add(delta));
+            }
+        }
+        Heuristic::from_weights(weights)
+    }
+
+    pub fn play_match(&self, board: &Board, first: Heuristic, second: Heuristic) -> i32 {
+        let first_engine = Negamax::with_evaluator(first);
+        let second_engine = Negamax::with_evaluator(second);
+        let mut score = 0;
+        let mut opening = (board.clone(), Mark::X);
+        for game in 0..self.games {
+            if game & 1 == 0 {
+                opening = self.opening(board);
+                score += self.result(&opening, &first_engine, &second_engine);
+            } else {
+                score -= self.result(&opening, &second_engine, &first_engine);
+            }
+        }
+        score
+    }
+
+    fn opening(&self, board: &Board) -> (Board, Mark) {
+        let mut opening = board.clone();
+        let mut mark = Mark::X;
+        for _ in 0..self.opening_plies {
+            match opening.playable_moves(mark) {
+                Ok(moves) => {
+                    let index = self.rng.below(moves.len());
+                    let player_move = *moves.get(index).expect("index is below the length");
+                    opening.apply_move(player_move, mark);
+                    mark = mark.opposite();
+                }
+                Err(MoveError::GameOver | MoveError::NoLegalMoves) => break,
+            }
+        }
+        (opening, mark)
+    }
+
+    fn result(&self, opening: &(Board, Mark), x_engine: &Negamax, o_engine: &Negamax) -> i32 {
+        let (start, to_move) = opening;
+        let players = [(x_engine, Mark::X), (o_engine, Mark::O)];
+        let mut board = start.clone();
+        let mut blocked = None;
+        let skip = usize::from(*to_move == Mark::O);
+        for &(engine, mark) in players.iter().cycle().skip(skip).take(Self::MAX_PLIES) {
+            match Ai::search(engine, &board, mark, &self.limits) {
+                Ok(player_move) => board.apply_move(player_move, mark),
+                Err(MoveError::GameOver) => break,
+                Err(MoveError::NoLegalMoves) => {
+                    blocked = Some(mark);
+                    break;
+                }
+            }
+        }
+        match board
+            .winner()
+            .copied()
+            .or_else(|| blocked.map(Mark::opposite))
+        {
+            Some(Mark::X) => 1,
+            Some(Mark::O) => -1,
+            None => 0,
+        }
+    }
+
+    fn clamp(weight: i16) -> i16 {
+        weight.clamp(0, Self::MAX_WEIGHT)
+    }
+
+    fn with_rng(iterations: usize, games: usize, rng: Rng) -> Self {
+        Self {
+            iterations,
+            games,
+            step: Self::STEP,
+            opening_plies: Self::OPENING_PLIES,
+            limits: SearchLimits {
+                max_depth: Some(2),
+                threads: Some(1),
+                ..SearchLimits::default()
+            },
+            rng,
+        }
+    }
+}
+
+impl Default for Tuner {
+    fn default() -> Self {
+        Self::with_rng(Self::ITERATIONS, Self::GAMES, Rng::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_common::new_board;
+
+    #[test]
+    fn it_scores_a_match_between_equal_weights_as_even() {
+        let tuner = Tuner::new(1, 6, 3);
+        let heuristic = Heuristic::default();
+        assert_eq!(0, tuner.play_match(&new_board(), heuristic, heuristic));
+    }
+
+    #[test]
+    fn it_scores_a_match_from_the_first_players_side() {
+        let blind = Heuristic::from_weights([0; 5]);
+        let score = Tuner::new(1, 4, 5).play_match(&new_board(), Heuristic::default(), blind);
+        let reversed = Tuner::new(1, 4, 5).play_match(&new_board(), blind, Heuristic::default());
+        assert_eq!(-score, reversed);
+    }
+
+    #[test]
+    fn it_keeps_tuned_weights_within_bounds() {
+        let start = Heuristic::from_weights([0, 1, 199, 200, 2]);
+        let tuned = Tuner::new(4, 2, 11).tune(&new_board(), start);
+        assert!(tuned
+            .weights()
+            .iter()
+            .all(|&weight| (0..=Tuner::MAX_WEIGHT).contains(&weight)));
+    }
+
+    #[test]
+    fn it_tunes_deterministically_for_a_seed() {
+        let board = new_board();
+        let first = Tuner::new(3, 2, 7).tune(&board, Heuristic::default());
+        let second = Tuner::new(3, 2, 7).tune(&board, Heuristic::default());
+        assert_eq!(first, second);
+    }
+}
@@ -1,28 +1,72 @@
-use crate::{Mark, UsizeExt};
+use crate::{Cell, Mark, Move, MoveError, Phase, U64Ext, UsizeExt, Variant};
+use core::cmp;
 use std::fmt;
 
 #[derive(Clone, Debug)]
 pub struct Board {
-    grid: Vec<Option<Mark>>,
+    grid: Vec<Cell>,
     row_size: usize,
     size: usize,
     win_combos: Vec<Vec<usize>>,
+    adjacency: Vec<Vec<usize>>,
+    variant: Variant,
+    history: Vec<Ply>,
+    placement_order: Vec<usize>,
+    hash: u64,
+}
+
+#[derive(Clone, Debug)]
+struct Ply {
+    player_move: Move,
+    mark: Mark,
+    key: u64,
+    removed: Option<(usize, usize)>,
 }
 
 impl Board {
-    #[allow(clippy::panic)]
+    const REPETITION_LIMIT: usize = 3;
+
     pub fn new(size: usize) -> Self {
+        Self::with_variant(size, Variant::Standard)
+    }
+
+    #[allow(clippy::panic)]
+    pub fn with_variant(size: usize, variant: Variant) -> Self {
         if !size.is_perfect_sq() {
             panic!("board size is not a perfect square");
         }
-        let grid = vec![None; size];
         let row_size = size.sqrt();
-        let win_combos: Vec<Vec<usize>> = Self::gen_win_combos(size, row_size);
+        Self::with_dimensions(row_size, row_size, row_size, variant)
+    }
+
+    pub fn connect_four() -> Self {
+        Self::with_dimensions(6, 7, 4, Variant::Gravity)
+    }
+
+    #[allow(clippy::panic)]
+    pub fn with_dimensions(
+        rows: usize,
+        row_size: usize,
+        win_length: usize,
+        variant: Variant,
+    ) -> Self {
+        if win_length == 0 || win_length > rows || win_length > row_size {
+            panic!("win length does not fit on the board");
+        }
+        let size = rows * row_size;
+        let grid = vec![Cell::Empty; size];
+        let win_combos: Vec<Vec<usize>> = Self::gen_win_combos(rows, row_size, win_length);
+        let adjacency = Self::gen_adjacency(rows, row_size);
         Self {
             grid,
             row_size,
             size,
             win_combos,
+            adjacency,
+            variant,
+            history: vec![],
+            placement_order: vec![],
+            hash: 0,
         }
     }
 
@@ -30,12 +74,16 @@ impl Board {
         self.size
     }
 
+    pub fn variant(&self) -> Variant {
+        self.variant
+    }
+
     pub fn row_size(&self) -> usize {
         #![allow(dead_code)]
         self.row_size
     }
 
-    pub fn iter(&self) -> impl Iterator<Item = &Option<Mark>> {
+    pub fn iter(&self) -> impl Iterator<Item = &Cell> {
         #![allow(dead_code)]
         self.grid.iter()
     }
@@ -44,16 +92,20 @@ impl Board {
         self.grid
             .iter()
             .enumerate()
-            .filter_map(|(i, n)| if n.is_none() { Some(i) } else { None })
+            .filter_map(|(i, n)| if n.is_empty() { Some(i) } else { None })
             .collect()
     }
 
     pub fn is_occupied(&self, index: usize) -> bool {
-        self._mark(index).is_some()
+        !self.grid[index].is_empty()
     }
 
     pub fn is_all_occupied(&self) -> bool {
-        self.grid.iter().all(Option::is_some)
+        !self.grid.iter().any(|cell| cell.is_empty())
+    }
+
+    pub fn is_blocked(&self, index: usize) -> bool {
+        self.grid[index] == Cell::Blocked
     }
 
     pub fn mark(&self, index: usize) -> Option<&Mark> {
@@ -68,12 +120,188 @@ impl Board {
         self._set_mark(index, Some(mark))
     }
 
+    pub fn set_blocked(&mut self, index: usize) -> Option<Mark> {
+        self._set_cell(index, Cell::Blocked)
+    }
+
     pub fn is_available_cell(&self, index: usize) -> bool {
-        index < self.size && !self.is_occupied(index)
+        index < self.size
+            && !self.is_occupied(index)
+            && (self.variant != Variant::Gravity
+                || self.drop_cell(index % self.row_size) == Some(index))
+    }
+
+    pub fn drop_cell(&self, column: usize) -> Option<usize> {
+        if column >= self.row_size {
+            return None;
+        }
+        (column..self.size)
+            .step_by(self.row_size)
+            .take_while(|&index| !self.is_occupied(index))
+            .last()
     }
 
     pub fn is_game_over(&self) -> bool {
-        self.is_all_occupied() || self.winner().is_some()
+        self.is_all_occupied() || self.winner().is_some() || self.is_repetition()
+    }
+
+    pub fn adjacent_cells(&self, index: usize) -> &[usize] {
+        &self.adjacency[index]
+    }
+
+    pub fn mark_count(&self, mark: Mark) -> usize {
+        self.grid
+            .iter()
+            .filter(|&&cell| cell == Cell::Marked(mark))
+            .count()
+    }
+
+    pub fn placement_order(&self, mark: Mark) -> Vec<usize> {
+        self.placement_order
+            .iter()
+            .copied()
+            .filter(|&index| self.grid[index] == Cell::Marked(mark))
+            .collect()
+    }
+
+    pub fn disappearing_cell(&self, mark: Mark) -> Option<usize> {
+        match self.variant {
+            Variant::Disappearing { marks } if self.mark_count(mark) >= marks => {
+                self.placement_order(mark).first().copied()
+            }
+            Variant::Standard
+            | Variant::Sliding { .. }
+            | Variant::Disappearing { .. }
+            | Variant::Gravity => None,
+        }
+    }
+
+    pub fn phase(&self, mark: Mark) -> Phase {
+        match self.variant {
+            Variant::Sliding { pieces } if self.mark_count(mark) >= pieces => Phase::Movement,
+            Variant::Standard
+            | Variant::Sliding { .. }
+            | Variant::Disappearing { .. }
+            | Variant::Gravity => Phase::Placement,
+        }
+    }
+
+    pub fn legal_moves(&self, mark: Mark) -> Vec<Move> {
+        match self.phase(mark) {
+            Phase::Placement if self.variant == Variant::Gravity => self
+                .columns_center_out()
+                .into_iter()
+                .filter_map(|column| self.drop_cell(column))
+                .map(Move::Place)
+                .collect(),
+            Phase::Placement => self
+                .empty_cell_indices()
+                .into_iter()
+                .map(Move::Place)
+                .collect(),
+            Phase::Movement => self
+                .grid
+                .iter()
+                .enumerate()
+                .filter(|&(_, &cell)| cell == Cell::Marked(mark))
+                .flat_map(|(from, _)| {
+                    self.adjacent_cells(from)
+                        .iter()
+                        .filter(|&&to| !self.is_occupied(to))
+                        .map(move |&to| Move::Slide { from, to })
+                })
+                .collect(),
+        }
+    }
+
+    pub fn playable_moves(&self, mark: Mark) -> Result<Vec<Move>, MoveError> {
+        if self.is_game_over() {
+            return Err(MoveError::GameOver);
+        }
+        let moves = self.legal_moves(mark);
+        if moves.is_empty() {
+            Err(MoveError::NoLegalMoves)
+        } else {
+            Ok(moves)
+        }
+    }
+
+    pub fn is_legal_move(&self, player_move: Move, mark: Mark) -> bool {
+        self.legal_moves(mark).contains(&player_move)
+    }
+
+    pub fn apply_move(&mut self, player_move: Move, mark: Mark) {
+        let mut removed = None;
+        match player_move {
+            Move::Place(index) => {
+                if let Some(oldest) = self.disappearing_cell(mark) {
+                    removed = self
+                        .placement_order
+                        .iter()
+                        .position(|&cell| cell == oldest)
+                        .map(|position| (oldest, position));
+                    self.reset_mark(oldest);
+                }
+                self.set_mark(index, mark);
+            }
+            Move::Slide { from, to } => {
+                self.reset_mark(from);
+                self.set_mark(to, mark);
+            }
+        }
+        let key = self.position_key();
+        self.history.push(Ply {
+            player_move,
+            mark,
+            key,
+            removed,
+        });
+    }
+
+    pub fn undo_move(&mut self) -> Option<Move> {
+        let ply = self.history.pop()?;
+        match ply.player_move {
+            Move::Place(index) => {
+                self.reset_mark(index);
+            }
+            Move::Slide { from, to } => {
+                self.reset_mark(to);
+                self.set_mark(from, ply.mark);
+            }
+        }
+        if let Some((index, position)) = ply.removed {
+            self.set_mark(index, ply.mark);
+            self.placement_order.pop();
+            self.placement_order.insert(position, index);
+        }
+        Some(ply.player_move)
+    }
+
+    pub(crate) fn can_repeat(&self) -> bool {
+        match self.variant {
+            Variant::Sliding { .. } | Variant::Disappearing { .. } => true,
+            Variant::Standard | Variant::Gravity => false,
+        }
+    }
+
+    pub(crate) fn ply_count(&self) -> usize {
+        self.history.len()
+    }
+
+    pub(crate) fn win_length(&self) -> usize {
+        self.win_combos.first().map_or(0, Vec::len)
+    }
+
+    pub fn is_repetition(&self) -> bool {
+        if let Some(last) = self.history.last() {
+            self.history
+                .iter()
+                .filter(|ply| ply.key == last.key && ply.mark == last.mark)
+                .count()
+                >= Self::REPETITION_LIMIT
+        } else {
+            false
+        }
     }
 
     pub fn winner(&self) -> Option<&Mark> {
@@ -91,47 +319,143 @@ impl Board {
         }
     }
 
-    fn win_combos(&self) -> &Vec<Vec<usize>> {
-        #![allow(dead_code)]
+    pub fn is_winning_move(&self, player_move: Move, mark: Mark) -> bool {
+        let (vacated, target) = match player_move {
+            Move::Place(index) => (self.disappearing_cell(mark), index),
+            Move::Slide { from, to } => (Some(from), to),
+        };
+        self.win_combos
+            .iter()
+            .filter(|combo| combo.contains(&target))
+            .any(|combo| {
+                combo.iter().all(|&index| {
+                    index == target || (Some(index) != vacated && self.mark(index) == Some(&mark))
+                })
+            })
+    }
+
+    pub(crate) fn win_combos(&self) -> &Vec<Vec<usize>> {
         &self.win_combos
     }
 
-    fn gen_win_combos(board_size: usize, row_size: usize) -> Vec<Vec<usize>> {
-        let grid: Vec<usize> = (0..board_size).collect();
-        let mut combos: Vec<Vec<usize>> = grid.chunks(row_size).map(|row| row.to_vec()).collect();
+    fn gen_win_combos(rows: usize, row_size: usize, win_length: usize) -> Vec<Vec<usize>> {
+        let offsets = 0..win_length;
+        let mut combos: Vec<Vec<usize>> = vec![];
+
+        for row in 0..rows {
+            for col in 0..=row_size - win_length {
+                combos.push(
+                    offsets
+                        .clone()
+                        .map(|offset| row * row_size + col + offset)
+                        .collect(),
+                );
+            }
+        }
 
         for col in 0..row_size {
-            let mut col_combo = vec![];
-            for row in grid.chunks(row_size) {
-                col_combo.push(row[col]);
+            for row in 0..=rows - win_length {
+                combos.push(
+                    offsets
+                        .clone()
+                        .map(|offset| (row + offset) * row_size + col)
+                        .collect(),
+                );
             }
-            combos.push(col_combo);
         }
 
-        combos.push(
-            grid.chunks(row_size)
-                .enumerate()
-                .map(|(index, row)| row[index])
-                .collect(),
-        );
+        for row in 0..=rows - win_length {
+            for col in 0..=row_size - win_length {
+                combos.push(
+                    offsets
+                        .clone()
+                        .map(|offset| (row + offset) * row_size + col + offset)
+                        .collect(),
+                );
+            }
+        }
 
-        combos.push(
-            grid.chunks(row_size)
-                .rev()
-                .enumerate()
-                .map(|(index, row)| row[index])
-                .collect(),
-        );
+        for row in win_length - 1..rows {
+            for col in 0..=row_size - win_length {
+                combos.push(
+                    offsets
+                        .clone()
+                        .map(|offset| (row - offset) * row_size + col + offset)
+                        .collect(),
+                );
+            }
+        }
         combos
     }
 
+    fn columns_center_out(&self) -> Vec<usize> {
+        let center = self.row_size - 1;
+        let mut columns: Vec<usize> = (0..self.row_size).collect();
+        columns.sort_by_key(|&column| cmp::max(column * 2, center) - cmp::min(column * 2, center));
+        columns
+    }
+
+    fn gen_adjacency(rows: usize, row_size: usize) -> Vec<Vec<usize>> {
+        let mut adjacency: Vec<Vec<usize>> = vec![vec![]; rows * row_size];
+        for row in 0..rows {
+            for col in 0..row_size {
+                let diagonal = (row + col) & 1 == 0;
+                for neighbour_row in row.saturating_sub(1)..cmp::min(row + 2, rows) {
+                    for neighbour_col in col.saturating_sub(1)..cmp::min(col + 2, row_size) {
+                        let orthogonal = neighbour_row == row || neighbour_col == col;
+                        if (neighbour_row, neighbour_col) != (row, col) && (orthogonal || diagonal)
+                        {
+                            adjacency[row * row_size + col]
+                                .push(neighbour_row * row_size + neighbour_col);
+                        }
+                    }
+                }
+            }
+        }
+        adjacency
+    }
+
+    #[allow(clippy::as_conversions)]
+    pub fn position_key(&self) -> u64 {
+        match self.variant {
+            Variant::Disappearing { .. } => {
+                self.placement_order.iter().fold(self.hash, |acc, &index| {
+                    acc.wrapping_mul(31).wrapping_add(index as u64 + 1)
+                })
+            }
+            Variant::Standard | Variant::Sliding { .. } | Variant::Gravity => self.hash,
+        }
+    }
+
     fn _mark(&self, index: usize) -> Option<&Mark> {
-        self.grid[index].as_ref()
+        self.grid[index].mark()
     }
 
     fn _set_mark(&mut self, index: usize, mark: Option<Mark>) -> Option<Mark> {
-        let old_mark = self.grid[index].take();
-        self.grid[index] = mark;
+        self._set_cell(index, Cell::from(mark))
+    }
+
+    #[allow(clippy::as_conversions)]
+    fn zobrist(index: usize, cell: Cell) -> u64 {
+        let code = match cell {
+            Cell::Empty => return 0,
+            Cell::Marked(Mark::O) => 1,
+            Cell::Marked(Mark::X) => 2,
+            Cell::Blocked => 3,
+        };
+        ((index as u64) << 2 | code).splitmix()
+    }
+
+    fn _set_cell(&mut self, index: usize, cell: Cell) -> Option<Mark> {
+        self.hash ^= Self::zobrist(index, self.grid[index]) ^ Self::zobrist(index, cell);
+        let old_mark = self.grid[index].mark().copied();
+        if old_mark.is_some() {
+            self.placement_order.retain(|&placed| placed != index);
+        }
+        if cell.mark().is_some() {
+            self.placement_order.push(index);
+        }
+        self.grid[index] = cell;
         old_mark
     }
 
@@ -149,31 +473,36 @@ impl Board {
 impl fmt::Display for Board {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if f.alternate() {
-            let rows = self.grid.chunks(self.row_size).enumerate().fold(
+            let is_gravity = self.variant == Variant::Gravity;
+            let mut rows = self.grid.chunks(self.row_size).enumerate().fold(
                 vec![],
                 |mut acc, (row_index, row)| {
                     let row = row
                         .iter()
                         .enumerate()
                         .map(|(index, cell)| match cell {
-                            Some(cell) => cell.to_string(),
-                            None => (self.row_size * row_index + index).to_string(),
+                            Cell::Marked(_) | Cell::Blocked => cell.to_string(),
+                            Cell::Empty if is_gravity => cell.to_string(),
+                            Cell::Empty => (self.row_size * row_index + index).to_string(),
                         })
                         .collect::<Vec<String>>();
                     acc.push(format!(" {}\n", row.join(" | ")));
                     acc
                 },
             );
+            if is_gravity {
+                let columns = (0..self.row_size)
+                    .map(|column| column.to_string())
+                    .collect::<Vec<String>>();
+                rows.push(format!(" {}\n", columns.join(" | ")));
+            }
 
             write!(f, "{}", rows.join(&self.grid_line()))
         } else {
             let board_str: String = self
                 .grid
                 .iter()
-                .map(|cell| match cell {
-                    Some(cell) => cell.to_string(),
-                    None => " ".to_string(),
-                })
+                .map(Cell::to_string)
                 .collect::<Vec<String>>()
                 .join("");
             write!(f, "{}", board_str)
@@ -183,17 +512,24 @@ impl fmt::Display for Board {
 
 #[macro_export]
 macro_rules! board {
-    ($x:expr) => {{
+    ($x:expr) => {
+        board![$x, Variant::Standard]
+    };
+    ($x:expr, $variant:expr) => {{
         let board_size = $x.len();
-        let mut board = Board::new(board_size);
+        let mut board = Board::with_variant(board_size, $variant);
         for (index, ch) in (&$x.to_uppercase()).chars().enumerate() {
-            let opt_mark: Option<Mark> = match ch {
-                'O' => Some(Mark::O),
-                'X' => Some(Mark::X),
-                _ => None,
-            };
-            if let Some(mark) = opt_mark {
-                board.set_mark(index, mark);
+            match ch {
+                'O' => {
+                    board.set_mark(index, Mark::O);
+                }
+                'X' => {
+                    board.set_mark(index, Mark::X);
+                }
+                '#' => {
+                    board.set_blocked(index);
+                }
+                _ => {}
             }
         }
         board
@@ -221,7 +557,7 @@ mod tests {
     fn it_iterates_over_grid() {
         let board = new_board();
         for cell in board.iter() {
-            assert_eq!(None, cell.as_ref());
+            assert_eq!(&Cell::Empty, cell);
         }
     }
 
@@ -387,6 +723,355 @@ mod tests {
         }
     }
 
+    #[test]
+    fn it_creates_a_new_board_with_a_variant() {
+        let variant = Variant::Sliding { pieces: 3 };
+        let board = board!["X   O    ", variant];
+        assert_eq!(variant, board.variant());
+        assert_eq!(Some(&Mark::X), board.mark(0));
+        assert_eq!(Variant::Standard, new_board().variant());
+    }
+
+    #[test]
+    fn it_generates_adjacency_from_the_drawn_lines() {
+        let board = new_board();
+        assert_eq!(&[1, 3, 4], board.adjacent_cells(0));
+        assert_eq!(&[0, 2, 4], board.adjacent_cells(1));
+        assert_eq!(&[0, 1, 2, 3, 5, 6, 7, 8], board.adjacent_cells(4));
+        assert_eq!(&[4, 6, 8], board.adjacent_cells(7));
+        let large = Board::with_dimensions(4, 4, 4, Variant::Sliding { pieces: 4 });
+        assert_eq!(&[0, 1, 2, 4, 6, 8, 9, 10], large.adjacent_cells(5));
+        assert_eq!(&[2, 5, 7, 10], large.adjacent_cells(6));
+        assert_eq!(&[8, 13], large.adjacent_cells(12));
+    }
+
+    #[test]
+    fn it_counts_marks() {
+        let board = board!["XOX O    "];
+        assert_eq!(2, board.mark_count(Mark::X));
+        assert_eq!(2, board.mark_count(Mark::O));
+    }
+
+    #[test]
+    fn it_switches_to_movement_phase_when_pieces_are_placed() {
+        let board = board!["XXOOO    ", Variant::Sliding { pieces: 3 }];
+        assert_eq!(Phase::Placement, board.phase(Mark::X));
+        assert_eq!(Phase::Movement, board.phase(Mark::O));
+        let board = board!["XXOOO    "];
+        assert_eq!(Phase::Placement, board.phase(Mark::O));
+    }
+
+    #[test]
+    fn it_returns_placements_as_legal_moves() {
+        let board = board!["XO XO XO "];
+        let expected = vec![Move::Place(2), Move::Place(5), Move::Place(8)];
+        assert_eq!(expected, board.legal_moves(Mark::X));
+    }
+
+    #[test]
+    fn it_returns_slides_as_legal_moves() {
+        let board = board!["XXOOOX   ", Variant::Sliding { pieces: 3 }];
+        let expected = vec![
+            Move::Slide { from: 3, to: 6 },
+            Move::Slide { from: 4, to: 6 },
+            Move::Slide { from: 4, to: 7 },
+            Move::Slide { from: 4, to: 8 },
+        ];
+        assert_eq!(expected, board.legal_moves(Mark::O));
+        assert!(board.is_legal_move(Move::Slide { from: 3, to: 6 }, Mark::O));
+        assert!(!board.is_legal_move(Move::Place(6), Mark::O));
+        assert!(!board.is_legal_move(Move::Slide { from: 2, to: 6 }, Mark::O));
+        assert!(!board.is_legal_move(Move::Slide { from: 3, to: 7 }, Mark::O));
+    }
+
+    #[test]
+    fn it_applies_and_undoes_moves() {
+        let mut board = board!["XXOOOX   ", Variant::Sliding { pieces: 3 }];
+        board.apply_move(Move::Slide { from: 3, to: 6 }, Mark::O);
+        assert_eq!(None, board.mark(3));
+        assert_eq!(Some(&Mark::O), board.mark(6));
+        assert_eq!(Some(&Mark::O), board.winner());
+        assert_eq!(Some(Move::Slide { from: 3, to: 6 }), board.undo_move());
+        assert_eq!(Some(&Mark::O), board.mark(3));
+        assert_eq!(None, board.mark(6));
+        board.apply_move(Move::Place(7), Mark::X);
+        assert_eq!(Some(Move::Place(7)), board.undo_move());
+        assert_eq!(None, board.mark(7));
+        assert_eq!(None, board.undo_move());
+    }
+
+    #[test]
+    fn it_detects_threefold_repetition() {
+        let mut board = board!["XO O X XO", Variant::Sliding { pieces: 3 }];
+        let shuffle = [
+            (Move::Slide { from: 5, to: 2 }, Mark::X),
+            (Move::Slide { from: 3, to: 6 }, Mark::O),
+            (Move::Slide { from: 2, to: 5 }, Mark::X),
+            (Move::Slide { from: 6, to: 3 }, Mark::O),
+        ];
+        for _ in 0..2 {
+            for &(player_move, mark) in &shuffle {
+                assert!(!board.is_repetition());
+                board.apply_move(player_move, mark);
+            }
+        }
+        assert!(!board.is_repetition());
+        assert!(!board.is_game_over());
+        board.apply_move(shuffle[0].0, shuffle[0].1);
+        assert!(board.is_repetition());
+        assert!(board.is_game_over());
+    }
+
+    #[test]
+    fn it_hashes_positions_incrementally() {
+        let mut board = new_board();
+        assert_eq!(0, board.position_key());
+        board.apply_move(Move::Place(0), Mark::X);
+        board.apply_move(Move::Place(4), Mark::O);
+        let key = board.position_key();
+        assert_eq!(key, board!["X   O    "].position_key());
+        assert_ne!(key, board!["O   X    "].position_key());
+        board.undo_move();
+        board.undo_move();
+        assert_eq!(0, board.position_key());
+        assert_ne!(0, board!["#        "].position_key());
+    }
+
+    #[test]
+    fn it_hashes_placement_order_in_disappearing_mode() {
+        let first = disappearing_board(&[0, 1], &[4]);
+        let second = disappearing_board(&[1, 0], &[4]);
+        assert_ne!(first.position_key(), second.position_key());
+        let first = board!["XX  O    "];
+        let mut second = new_board();
+        second.set_mark(1, Mark::X);
+        second.set_mark(0, Mark::X);
+        second.set_mark(4, Mark::O);
+        assert_eq!(first.position_key(), second.position_key());
+    }
+
+    #[test]
+    fn it_tracks_placement_order() {
+        let mut board = new_board();
+        board.set_mark(4, Mark::X);
+        board.set_mark(0, Mark::O);
+        board.set_mark(2, Mark::X);
+        assert_eq!(vec![4, 2], board.placement_order(Mark::X));
+        assert_eq!(vec![0], board.placement_order(Mark::O));
+        board.reset_mark(4);
+        assert_eq!(vec![2], board.placement_order(Mark::X));
+    }
+
+    #[test]
+    fn it_returns_the_disappearing_cell() {
+        let variant = Variant::Disappearing { marks: 3 };
+        let board = disappearing_board(&[3, 0, 1], &[4, 6]);
+        assert_eq!(variant, board.variant());
+        assert_eq!(Some(3), board.disappearing_cell(Mark::X));
+        assert_eq!(None, board.disappearing_cell(Mark::O));
+        assert_eq!(None, board!["XXX      "].disappearing_cell(Mark::X));
+    }
+
+    #[test]
+    fn it_removes_the_oldest_mark_on_placement() {
+        let mut board = disappearing_board(&[3, 0, 1], &[4, 6]);
+        board.apply_move(Move::Place(2), Mark::X);
+        assert_eq!(None, board.mark(3));
+        assert_eq!(vec![0, 1, 2], board.placement_order(Mark::X));
+        assert_eq!(Some(&Mark::X), board.winner());
+        board.undo_move();
+        assert_eq!(Some(&Mark::X), board.mark(3));
+        assert_eq!(None, board.mark(2));
+        assert_eq!(vec![3, 0, 1], board.placement_order(Mark::X));
+    }
+
+    #[test]
+    fn it_removes_a_mark_from_the_line_being_made() {
+        let mut board = disappearing_board(&[0, 1, 3], &[4, 6]);
+        board.apply_move(Move::Place(2), Mark::X);
+        assert_eq!(None, board.mark(0));
+        assert_eq!(None, board.winner());
+        assert!(!board.is_game_over());
+    }
+
+    #[test]
+    fn it_never_fills_a_disappearing_board() {
+        let mut board = disappearing_board(&[], &[]);
+        let mut mark = Mark::X;
+        for index in &[0, 1, 2, 4, 3, 5, 7, 6, 8] {
+            board.apply_move(Move::Place(*index), mark);
+            mark = mark.opposite();
+        }
+        assert_eq!(3, board.mark_count(Mark::X));
+        assert_eq!(3, board.mark_count(Mark::O));
+        assert!(!board.is_all_occupied());
+    }
+
+    #[test]
+    fn it_creates_a_connect_four_board() {
+        let board = Board::connect_four();
+        assert_eq!(42, board.size());
+        assert_eq!(7, board.row_size());
+        assert_eq!(Variant::Gravity, board.variant());
+        assert_eq!(69, board.win_combos().len());
+    }
+
+    #[test]
+    fn it_generates_win_combos_shorter_than_a_row() {
+        let board = Board::with_dimensions(3, 4, 3, Variant::Standard);
+        let expectation: Vec<Vec<usize>> = vec![
+            vec![0, 1, 2],
+            vec![1, 2, 3],
+            vec![4, 5, 6],
+            vec![5, 6, 7],
+            vec![8, 9, 10],
+            vec![9, 10, 11],
+            vec![0, 4, 8],
+            vec![1, 5, 9],
+            vec![2, 6, 10],
+            vec![3, 7, 11],
+            vec![0, 5, 10],
+            vec![1, 6, 11],
+            vec![8, 5, 2],
+            vec![9, 6, 3],
+        ];
+        assert_eq!(&expectation, board.win_combos());
+    }
+
+    #[test]
+    fn it_drops_marks_to_the_lowest_empty_cell() {
+        let mut board = Board::connect_four();
+        assert_eq!(Some(38), board.drop_cell(3));
+        board.set_mark(38, Mark::X);
+        assert_eq!(Some(31), board.drop_cell(3));
+        assert_eq!(None, board.drop_cell(7));
+        for index in (3..31).step_by(7) {
+            board.set_mark(index, Mark::O);
+        }
+        assert_eq!(None, board.drop_cell(3));
+    }
+
+    #[test]
+    fn it_returns_columns_as_legal_moves_center_first() {
+        let mut board = Board::connect_four();
+        board.set_mark(38, Mark::X);
+        let expected: Vec<Move> = [31, 37, 39, 36, 40, 35, 41]
+            .iter()
+            .map(|&index| Move::Place(index))
+            .collect();
+        assert_eq!(expected, board.legal_moves(Mark::O));
+    }
+
+    #[test]
+    fn it_checks_valid_mark_placement_in_gravity_mode() {
+        let mut board = Board::connect_four();
+        assert!(board.is_available_cell(38));
+        assert!(!board.is_available_cell(31));
+        board.apply_move(Move::Place(38), Mark::X);
+        assert!(board.is_available_cell(31));
+        assert!(!board.is_available_cell(38));
+    }
+
+    #[test]
+    fn it_checks_for_four_in_a_row_in_gravity_mode() {
+        let mut board = Board::connect_four();
+        for &index in &[35, 29, 23] {
+            board.set_mark(index, Mark::O);
+        }
+        assert_eq!(None, board.winner());
+        board.set_mark(17, Mark::O);
+        assert_eq!(Some(&Mark::O), board.winner());
+    }
+
+    #[test]
+    fn it_pretty_prints_the_board_in_gravity_mode() {
+        let mut board = Board::with_dimensions(3, 3, 3, Variant::Gravity);
+        board.set_mark(7, Mark::X);
+        let board_string = r#"   |   |  
+---+---+---
+   |   |  
+---+---+---
+   | X |  
+---+---+---
+ 0 | 1 | 2
+"#;
+
+        assert_eq!(board_string, format!("{:#}", board));
+    }
+
+    #[test]
+    fn it_blocks_cells() {
+        let mut board = new_board();
+        board.set_mark(4, Mark::X);
+        assert_eq!(Some(Mark::X), board.set_blocked(4));
+        assert!(board.is_blocked(4));
+        assert!(board.is_occupied(4));
+        assert!(!board.is_available_cell(4));
+        assert_eq!(None, board.mark(4));
+        assert_eq!(vec![0, 1, 2, 3, 5, 6, 7, 8], board.empty_cell_indices());
+        assert!(board.placement_order(Mark::X).is_empty());
+    }
+
+    #[test]
+    fn it_parses_blocked_cells() {
+        let board_str = "X#O  #   ";
+        let board = board![board_str];
+        assert!(board.is_blocked(1));
+        assert!(board.is_blocked(5));
+        assert!(!board.is_blocked(0));
+        assert_eq!(board_str, format!("{}", board));
+    }
+
+    #[test]
+    fn it_breaks_lines_with_blocked_cells() {
+        let board = board!["XXX#     "];
+        assert_eq!(Some(&Mark::X), board.winner());
+        let board = board!["X#X X  X "];
+        assert_eq!(None, board.winner());
+        let mut board = Board::with_dimensions(2, 5, 2, Variant::Standard);
+        board.set_mark(0, Mark::O);
+        board.set_blocked(1);
+        board.set_mark(2, Mark::O);
+        assert_eq!(None, board.winner());
+        board.set_mark(3, Mark::O);
+        assert_eq!(Some(&Mark::O), board.winner());
+    }
+
+    #[test]
+    fn it_fills_a_board_with_blocked_cells() {
+        let board = board!["XO#OX#XO#"];
+        assert!(board.is_all_occupied());
+        assert!(board.is_game_over());
+        assert!(board.legal_moves(Mark::X).is_empty());
+    }
+
+    #[test]
+    fn it_pretty_prints_blocked_cells() {
+        let board = board![" # O  X# "];
+        let board_string = r#" 0 | # | 2
+---+---+---
+ O | 4 | 5
+---+---+---
+ X | # | 8
+"#;
+
+        assert_eq!(board_string, format!("{:#}", board));
+    }
+
+    #[test]
+    fn it_stacks_marks_on_blocked_cells_in_gravity_mode() {
+        let mut board = Board::connect_four();
+        board.set_blocked(31);
+        assert_eq!(Some(24), board.drop_cell(3));
+        assert!(!board.is_available_cell(38));
+    }
+
+    #[test]
+    #[should_panic(expected = "win length does not fit on the board")]
+    fn it_panics_if_win_length_does_not_fit() {
+        Board::with_dimensions(3, 4, 4, Variant::Standard);
+    }
+
     #[test]
     #[should_panic(expected = "board size is not a perfect square")]
     fn it_panics_if_board_str_is_not_a_perfect_square() {
@@ -399,4 +1084,49 @@ mod tests {
     fn it_panics_if_board_size_is_not_a_perfect_square() {
         Board::new(10);
     }
+
+    #[test]
+    fn it_detects_winning_moves_without_playing_them() {
+        let board = board!["XX OO    "];
+        assert!(board.is_winning_move(Move::Place(2), Mark::X));
+        assert!(!board.is_winning_move(Move::Place(2), Mark::O));
+        assert!(board.is_winning_move(Move::Place(5), Mark::O));
+        assert!(!board.is_winning_move(Move::Place(8), Mark::X));
+    }
+
+    #[test]
+    fn it_detects_winning_slides_and_disappearing_placements() {
+        let board = board!["XXOOOX   ", Variant::Sliding { pieces: 3 }];
+        assert!(board.is_winning_move(Move::Slide { from: 3, to: 6 }, Mark::O));
+        assert!(!board.is_winning_move(Move::Slide { from: 4, to: 6 }, Mark::O));
+        let board = disappearing_board(&[0, 1, 4], &[3, 5, 8]);
+        assert!(!board.is_winning_move(Move::Place(2), Mark::X));
+        assert!(board.is_winning_move(Move::Place(7), Mark::X));
+    }
+
+    #[test]
+    fn it_explains_why_there_are_no_playable_moves() {
+        let board = board!["XX OO    "];
+        assert_eq!(
+            Ok(board.legal_moves(Mark::X)),
+            board.playable_moves(Mark::X)
+        );
+        let board = board!["XXXOO    "];
+        assert_eq!(Err(MoveError::GameOver), board.playable_moves(Mark::O));
+        let board = board!["XOXXOOOXX"];
+        assert_eq!(Err(MoveError::GameOver), board.playable_moves(Mark::O));
+        let board = board!["OXXXOOXO ", Variant::Sliding { pieces: 4 }];
+        assert_eq!(Err(MoveError::NoLegalMoves), board.playable_moves(Mark::X));
+    }
+
+    fn disappearing_board(x_cells: &[usize], o_cells: &[usize]) -> Board {
+        let mut board = Board::with_variant(9, Variant::Disappearing { marks: 3 });
+        for index in x_cells {
+            board.set_mark(*index, Mark::X);
+        }
+        for index in o_cells {
+            board.set_mark(*index, Mark::O);
+        }
+        board
+    }
 }
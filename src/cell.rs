@@ -0,0 +1,70 @@
+use crate::Mark;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Cell {
+    Empty,
+    Blocked,
+    Marked(Mark),
+}
+
+impl Cell {
+    pub fn mark(&self) -> Option<&Mark> {
+        match self {
+            Self::Marked(mark) => Some(mark),
+            Self::Empty | Self::Blocked => None,
+        }
+    }
+
+    pub fn is_empty(self) -> bool {
+        self == Self::Empty
+    }
+}
+
+impl From<Option<Mark>> for Cell {
+    fn from(mark: Option<Mark>) -> Self {
+        mark.map_or(Self::Empty, Self::Marked)
+    }
+}
+
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Empty => write!(f, " "),
+            Self::Blocked => write!(f, "#"),
+            Self::Marked(mark) => write!(f, "{}", mark),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_returns_the_mark() {
+        assert_eq!(Some(&Mark::X), Cell::Marked(Mark::X).mark());
+        assert_eq!(None, Cell::Empty.mark());
+        assert_eq!(None, Cell::Blocked.mark());
+    }
+
+    #[test]
+    fn it_checks_if_cell_is_empty() {
+        assert!(Cell::Empty.is_empty());
+        assert!(!Cell::Blocked.is_empty());
+        assert!(!Cell::Marked(Mark::O).is_empty());
+    }
+
+    #[test]
+    fn it_converts_from_an_optional_mark() {
+        assert_eq!(Cell::Marked(Mark::O), Cell::from(Some(Mark::O)));
+        assert_eq!(Cell::Empty, Cell::from(None));
+    }
+
+    #[test]
+    fn it_returns_cell_as_string() {
+        assert_eq!(" ", Cell::Empty.to_string());
+        assert_eq!("#", Cell::Blocked.to_string());
+        assert_eq!("X", Cell::Marked(Mark::X).to_string());
+    }
+}
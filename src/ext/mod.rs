@@ -1,3 +1,5 @@
+mod u64_ext;
 mod usize_ext;
 
+pub(crate) use u64_ext::U64Ext;
 pub(crate) use usize_ext::UsizeExt;
@@ -0,0 +1,24 @@
+pub(crate) trait U64Ext {
+    fn splitmix(self) -> u64;
+}
+
+impl U64Ext for u64 {
+    fn splitmix(self) -> u64 {
+        let mut z = self.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_mixes_bits_deterministically() {
+        assert_eq!(0xe220_a839_7b1d_cdaf, 0_u64.splitmix());
+        assert_eq!(1_u64.splitmix(), 1_u64.splitmix());
+        assert_ne!(1_u64.splitmix(), 2_u64.splitmix());
+    }
+}
@@ -1,4 +1,7 @@
-use crate::{Board, Computer, ConsoleIo, Human, Mark, Player, StdIo, Ui};
+use crate::{
+    Board, Computer, ConsoleIo, Handicap, Human, Mark, Move, MoveError, Negamax, Options, Phase,
+    Player, StdIo, Ui, Variant,
+};
 use core::fmt::Debug;
 
 #[derive(Debug)]
@@ -6,6 +9,7 @@ pub struct Game<U: StdIo> {
     board: Board,
     players: Vec<Box<dyn Player>>,
     ui: Ui<U>,
+    show_stats: bool,
 }
 
 impl<U> Game<U>
@@ -13,31 +17,59 @@ where
     U: StdIo,
 {
     pub fn new(board: Board, players: Vec<Box<dyn Player>>, ui: Ui<U>) -> Self {
-        Game { board, players, ui }
+        Self::with_stats(board, players, ui, false)
+    }
+
+    pub fn with_stats(
+        board: Board,
+        players: Vec<Box<dyn Player>>,
+        ui: Ui<U>,
+        show_stats: bool,
+    ) -> Self {
+        Game {
+            board,
+            players,
+            ui,
+            show_stats,
+        }
     }
 
     pub fn run(&mut self) {
+        let mut blocked = None;
         for player in self.players.iter().cycle() {
-            if self.board.is_game_over() {
-                break;
-            }
-            self.print_board();
-            let player_move = loop {
-                self.ui.print_turn_message(&player.mark().to_string());
-                let position = player.get_move(&self.board);
-                if self.board.is_available_cell(position) {
-                    break position;
+            let next_move = self
+                .board
+                .playable_moves(player.mark())
+                .and_then(|_| self.next_move(player.as_ref()));
+            match next_move {
+                Ok(player_move) => {
+                    self.board.apply_move(player_move, player.mark());
+                    if let Some(stats) = player.stats().filter(|_| self.show_stats) {
+                        self.ui.print_stats(&stats);
+                    }
+                }
+                Err(MoveError::GameOver) => break,
+                Err(MoveError::NoLegalMoves) => {
+                    blocked = Some(player.mark());
+                    break;
                 }
-            };
-            self.board.set_mark(player_move, player.mark());
+            }
         }
 
         self.print_board();
-        if let Some(winner) = self.board.winner() {
-            self.ui.print_winner(&winner.to_string());
+        let winner = self
+            .board
+            .winner()
+            .copied()
+            .or_else(|| blocked.map(Mark::opposite));
+        if let Some(mark) = winner {
+            self.ui.print_winner(&mark.to_string());
         } else {
             self.ui.print_draw();
         }
+        for player in &self.players {
+            player.game_over(&self.board, winner);
+        }
     }
 
     pub fn board(&self) -> &Board {
@@ -48,6 +80,26 @@ where
         &mut self.ui
     }
 
+    fn next_move(&self, player: &dyn Player) -> Result<Move, MoveError> {
+        self.print_board();
+        loop {
+            self.ui.print_turn_message(&player.mark().to_string());
+            if self.board.phase(player.mark()) == Phase::Movement {
+                self.ui.print_slide_message();
+            }
+            if self.board.variant() == Variant::Gravity {
+                self.ui.print_column_message();
+            }
+            if let Some(index) = self.board.disappearing_cell(player.mark()) {
+                self.ui.print_disappear_message(&index.to_string());
+            }
+            let player_move = player.get_move(&self.board)?;
+            if self.board.is_legal_move(player_move, player.mark()) {
+                return Ok(player_move);
+            }
+        }
+    }
+
     fn print_board(&self) {
         self.ui.print(&format!("\n{:#}", &self.board));
     }
@@ -55,13 +107,20 @@ where
 
 impl Game<ConsoleIo> {
     pub fn run_with_defaults() {
-        let board = Board::new(9);
+        Self::run_with_options(&Options::default());
+    }
+
+    pub fn run_with_options(options: &Options) {
+        let board = options.board();
         let ui = Ui::with_defaults();
-        let players: Vec<Box<dyn Player>> = vec![
-            Box::new(Human::with_defaults(Mark::X)),
-            Box::new(Computer::with_defaults(Mark::O)),
-        ];
-        let mut game = Self::new(board, players, ui);
+        let ai = || match options.seed {
+            Some(seed) => Negamax::with_handicap(Handicap::from(options.difficulty), seed),
+            None => Negamax::with_difficulty(options.difficulty),
+        };
+        let computer = Computer::new(ai(), Mark::O);
+        let human = Human::with_hint_limits(Mark::X, Ui::with_defaults(), ai(), *computer.limits());
+        let players: Vec<Box<dyn Player>> = vec![Box::new(human), Box::new(computer)];
+        let mut game = Self::with_stats(board, players, ui, options.stats);
         game.run();
     }
 }
@@ -13,24 +13,38 @@
 
 mod ai;
 mod board;
+mod cell;
 mod ext;
 mod game;
 mod mark;
+mod moves;
 mod player;
 mod run;
+mod sparse_board;
 mod std_io;
 mod ui;
+mod variant;
 
-use ai::{Ai, Negamax};
+#[cfg(not(target_arch = "wasm32"))]
+pub use ai::SearchHandle;
+pub use ai::{
+    Ai, Analysis, CancelToken, Difficulty, Evaluator, Handicap, Heuristic, Hint, Mcts, Menace,
+    MoveScore, Negamax, Outcome, Playout, Progress, ProofNumber, QLearning, SearchLimits,
+    SearchResult, SearchStats, Solution, Solver, Tablebase, TablebaseEntry, ThreatSpace, Tuner,
+};
 pub use board::Board;
-use ext::UsizeExt;
+pub use cell::Cell;
+use ext::{U64Ext, UsizeExt};
 pub use game::Game;
 pub use mark::Mark;
+pub use moves::{Move, MoveError, ParseMoveError};
 pub use player::{Computer, Human, Player};
-pub use run::run;
+pub use run::{run, Options};
+pub use sparse_board::{Coord, SparseBoard, Viewport};
 pub(crate) use std_io::ConsoleIo;
 pub use std_io::StdIo;
 pub use ui::Ui;
+pub use variant::{Phase, Variant};
 
 #[cfg(test)]
 mod test_common;
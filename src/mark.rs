@@ -1,7 +1,7 @@
 use std::fmt;
 
 #[repr(u8)]
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum Mark {
     O = 0,
     X = 1,
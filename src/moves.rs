@@ -0,0 +1,74 @@
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Move {
+    Place(usize),
+    Slide { from: usize, to: usize },
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Place(index) => write!(f, "{}", index),
+            Self::Slide { from, to } => write!(f, "{}-{}", from, to),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ParseMoveError;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MoveError {
+    GameOver,
+    NoLegalMoves,
+}
+
+impl FromStr for Move {
+    type Err = ParseMoveError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let cells = s
+            .split(|c: char| c == '-' || c.is_whitespace())
+            .filter(|part| !part.is_empty())
+            .map(str::parse::<usize>)
+            .collect::<Result<Vec<usize>, _>>()
+            .map_err(|_| ParseMoveError)?;
+        match cells[..] {
+            [index] => Ok(Self::Place(index)),
+            [from, to] => Ok(Self::Slide { from, to }),
+            _ => Err(ParseMoveError),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_displays_the_move() {
+        assert_eq!("4", Move::Place(4).to_string());
+        assert_eq!("6-7", Move::Slide { from: 6, to: 7 }.to_string());
+    }
+
+    #[test]
+    fn it_parses_a_placement() {
+        assert_eq!(Ok(Move::Place(4)), "4".parse::<Move>());
+    }
+
+    #[test]
+    fn it_parses_a_slide() {
+        let slide = Move::Slide { from: 6, to: 7 };
+        assert_eq!(Ok(slide), "6-7".parse::<Move>());
+        assert_eq!(Ok(slide), "6 7".parse::<Move>());
+    }
+
+    #[test]
+    fn it_rejects_bad_input() {
+        assert_eq!(Err(ParseMoveError), "bad".parse::<Move>());
+        assert_eq!(Err(ParseMoveError), "".parse::<Move>());
+        assert_eq!(Err(ParseMoveError), "1-2-3".parse::<Move>());
+    }
+}
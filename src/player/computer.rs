@@ -1,9 +1,13 @@
-use crate::{Ai, Board, Mark, Negamax, Player};
+use crate::{
+    Ai, Board, Difficulty, Mark, Move, MoveError, Negamax, Player, SearchLimits, SearchStats,
+};
+use std::time::Duration;
 
 #[derive(Debug)]
 pub struct Computer<T: Ai> {
     ai: T,
     mark: Mark,
+    limits: SearchLimits,
 }
 
 impl<T> Computer<T>
@@ -11,7 +15,15 @@ where
     T: Ai,
 {
     pub fn new(ai: T, mark: Mark) -> Computer<T> {
-        Computer { ai, mark }
+        Self::with_limits(ai, mark, SearchLimits::default())
+    }
+
+    pub fn with_limits(ai: T, mark: Mark, limits: SearchLimits) -> Computer<T> {
+        Computer { ai, mark, limits }
+    }
+
+    pub fn limits(&self) -> &SearchLimits {
+        &self.limits
     }
 }
 
@@ -20,43 +32,79 @@ impl Computer<Negamax> {
         let ai = Negamax::default();
         Self::new(ai, mark)
     }
+
+    pub fn with_difficulty(mark: Mark, difficulty: Difficulty) -> Computer<Negamax> {
+        let ai = Negamax::with_difficulty(difficulty);
+        Self::new(ai, mark)
+    }
+
+    pub fn with_move_time(mark: Mark, move_time: Duration) -> Computer<Negamax> {
+        let limits = SearchLimits {
+            move_time: Some(move_time),
+            ..SearchLimits::default()
+        };
+        Self::with_limits(Negamax::default(), mark, limits)
+    }
 }
 
 impl<T> Player for Computer<T>
 where
     T: Ai,
 {
-    fn get_move(&self, board: &Board) -> usize {
-        self.ai.search(board, self.mark)
+    fn get_move(&self, board: &Board) -> Result<Move, MoveError> {
+        self.ai.search(board, self.mark, &self.limits)
     }
 
     fn mark(&self) -> Mark {
         self.mark
     }
+
+    fn stats(&self) -> Option<SearchStats> {
+        self.ai.stats()
+    }
+
+    fn game_over(&self, board: &Board, winner: Option<Mark>) {
+        self.ai.game_over(board, self.mark, winner);
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::test_common::new_board;
+    use crate::{board, Variant};
     use std::cell::RefCell;
 
     struct DoubleAi {
-        moves: RefCell<Vec<usize>>,
+        moves: RefCell<Vec<Move>>,
+        limits: RefCell<Vec<SearchLimits>>,
+        results: RefCell<Vec<(Mark, Option<Mark>)>>,
     }
 
     impl DoubleAi {
-        fn new(moves: Vec<usize>) -> Self {
+        fn new(moves: Vec<Move>) -> Self {
             Self {
                 moves: RefCell::new(moves),
+                limits: RefCell::new(vec![]),
+                results: RefCell::new(vec![]),
             }
         }
     }
 
     impl Ai for DoubleAi {
-        fn search(&self, _board: &Board, _mark: Mark) -> usize {
+        fn search(
+            &self,
+            _board: &Board,
+            _mark: Mark,
+            limits: &SearchLimits,
+        ) -> Result<Move, MoveError> {
+            self.limits.borrow_mut().push(*limits);
             #[allow(clippy::unwrap_used)]
-            self.moves.borrow_mut().pop().unwrap()
+            Ok(self.moves.borrow_mut().pop().unwrap())
+        }
+
+        fn game_over(&self, _board: &Board, mark: Mark, winner: Option<Mark>) {
+            self.results.borrow_mut().push((mark, winner));
         }
     }
 
@@ -67,12 +115,67 @@ mod tests {
 
     #[test]
     fn it_returns_a_valid_move() {
-        let computer = new_computer(vec![1, 8]);
-        assert_eq!(8, computer.get_move(&new_board()));
-        assert_eq!(1, computer.get_move(&new_board()));
+        let computer = new_computer(vec![Move::Place(1), Move::Place(8)]);
+        assert_eq!(Ok(Move::Place(8)), computer.get_move(&new_board()));
+        assert_eq!(Ok(Move::Place(1)), computer.get_move(&new_board()));
+    }
+
+    #[test]
+    fn it_moves_within_a_time_limit() {
+        let computer = Computer::with_move_time(Mark::X, Duration::from_millis(50));
+        let board = new_board();
+        let player_move = computer.get_move(&board).expect("board is playable");
+        assert!(board.is_legal_move(player_move, Mark::X));
+    }
+
+    #[test]
+    fn it_passes_its_limits_to_the_ai() {
+        let limits = SearchLimits {
+            max_depth: Some(2),
+            threads: Some(1),
+            ..SearchLimits::default()
+        };
+        let ai = DoubleAi::new(vec![Move::Place(4)]);
+        let computer = Computer::with_limits(ai, Mark::X, limits);
+        assert_eq!(&limits, computer.limits());
+        assert_eq!(Ok(Move::Place(4)), computer.get_move(&new_board()));
+        assert_eq!(vec![limits], *computer.ai.limits.borrow());
+    }
+
+    #[test]
+    fn it_plays_legal_moves_at_every_difficulty() {
+        let difficulties = [
+            Difficulty::Easy,
+            Difficulty::Medium,
+            Difficulty::Hard,
+            Difficulty::Perfect,
+        ];
+        let board = new_board();
+        for &difficulty in &difficulties {
+            let computer = Computer::with_difficulty(Mark::X, difficulty);
+            let player_move = computer.get_move(&board).expect("board is playable");
+            assert!(board.is_legal_move(player_move, Mark::X));
+        }
+    }
+
+    #[test]
+    fn it_reports_when_the_game_is_over() {
+        let computer = Computer::with_defaults(Mark::O);
+        let board = board!["XXXOO    "];
+        assert_eq!(Err(MoveError::GameOver), computer.get_move(&board));
+    }
+
+    #[test]
+    fn it_tells_the_ai_when_the_game_is_over() {
+        let computer = new_computer(vec![]);
+        computer.game_over(&board!["XXXOO    "], Some(Mark::X));
+        assert_eq!(
+            vec![(Mark::X, Some(Mark::X))],
+            *computer.ai.results.borrow()
+        );
     }
 
-    fn new_computer(moves: Vec<usize>) -> Computer<DoubleAi> {
+    fn new_computer(moves: Vec<Move>) -> Computer<DoubleAi> {
         let ai = DoubleAi::new(moves);
         Computer::new(ai, Mark::X)
     }
@@ -1,23 +1,78 @@
-use crate::{Board, ConsoleIo, Mark, Player, StdIo, Ui};
+use crate::{
+    Ai, Board, ConsoleIo, Mark, Move, MoveError, Negamax, Player, SearchLimits, StdIo, Ui, Variant,
+};
 
 #[derive(Debug)]
 pub struct Human<T: StdIo> {
     mark: Mark,
     ui: Ui<T>,
+    hint: Option<Box<dyn Ai>>,
+    limits: SearchLimits,
 }
 
 impl<T> Human<T>
 where
     T: StdIo,
 {
+    const HINT_COMMAND: &'static str = "hint";
+
     pub fn new(mark: Mark, ui: Ui<T>) -> Human<T> {
-        Human { mark, ui }
+        Human {
+            mark,
+            ui,
+            hint: None,
+            limits: SearchLimits::default(),
+        }
+    }
+
+    pub fn with_hint<A: Ai + 'static>(mark: Mark, ui: Ui<T>, ai: A) -> Human<T> {
+        Self::with_hint_limits(mark, ui, ai, SearchLimits::default())
+    }
+
+    pub fn with_hint_limits<A: Ai + 'static>(
+        mark: Mark,
+        ui: Ui<T>,
+        ai: A,
+        limits: SearchLimits,
+    ) -> Human<T> {
+        Human {
+            mark,
+            ui,
+            hint: Some(Box::new(ai)),
+            limits,
+        }
+    }
+
+    fn print_hint(&self, board: &Board) {
+        let hint = self
+            .hint
+            .as_ref()
+            .and_then(|ai| ai.hint(board, self.mark, &self.limits).ok());
+        match hint {
+            Some(suggestion) => {
+                let cell_string = match suggestion.player_move {
+                    Move::Place(index) if board.variant() == Variant::Gravity => {
+                        Self::column(board, index).to_string()
+                    }
+                    Move::Place(_) | Move::Slide { .. } => suggestion.player_move.to_string(),
+                };
+                self.ui
+                    .print_hint(&cell_string, suggestion.score, suggestion.outcome);
+            }
+            None => self.ui.print_no_hint(),
+        }
+    }
+
+    fn column(board: &Board, index: usize) -> usize {
+        (0..board.row_size())
+            .find(|&column| board.drop_cell(column) == Some(index))
+            .unwrap_or(index)
     }
 }
 
 impl Human<ConsoleIo> {
     pub fn with_defaults(mark: Mark) -> Human<ConsoleIo> {
-        Self::new(mark, Ui::with_defaults())
+        Self::with_hint(mark, Ui::with_defaults(), Negamax::default())
     }
 }
 
@@ -25,11 +80,25 @@ impl<T> Player for Human<T>
 where
     T: StdIo,
 {
-    fn get_move(&self, _board: &Board) -> usize {
+    fn get_move(&self, board: &Board) -> Result<Move, MoveError> {
+        board.playable_moves(self.mark)?;
         loop {
             let move_str = self.ui.prompt();
-            if let Ok(value) = move_str.parse::<usize>() {
-                break value;
+            if move_str.trim() == Self::HINT_COMMAND {
+                self.print_hint(board);
+                continue;
+            }
+            let parsed = if board.variant() == Variant::Gravity {
+                move_str
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|column| board.drop_cell(column))
+                    .map(Move::Place)
+            } else {
+                move_str.parse::<Move>().ok()
+            };
+            if let Some(value) = parsed {
+                break Ok(value);
             }
         }
     }
@@ -42,7 +111,9 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::board;
     use crate::test_common::{new_board, DoubleStdIo};
+    use crate::{Mcts, Tablebase};
 
     #[test]
     fn it_returns_the_mark() {
@@ -55,14 +126,121 @@ mod tests {
     fn it_prompts_for_a_valid_move() {
         let std_io = DoubleStdIo::new(vec!["0"]);
         let ui = Ui::new(std_io);
-        assert_eq!(0, new_human(ui).get_move(&new_board()));
+        assert_eq!(Ok(Move::Place(0)), new_human(ui).get_move(&new_board()));
+    }
+
+    #[test]
+    fn it_prompts_for_a_slide() {
+        let std_io = DoubleStdIo::new(vec!["3-6"]);
+        let ui = Ui::new(std_io);
+        let expected = Move::Slide { from: 3, to: 6 };
+        assert_eq!(Ok(expected), new_human(ui).get_move(&new_board()));
     }
 
     #[test]
     fn it_retries_if_move_is_invalid() {
         let std_io = DoubleStdIo::new(vec!["2", "bad"]);
         let ui = Ui::new(std_io);
-        assert_eq!(2, new_human(ui).get_move(&new_board()));
+        assert_eq!(Ok(Move::Place(2)), new_human(ui).get_move(&new_board()));
+    }
+
+    #[test]
+    fn it_prompts_for_a_column_in_gravity_mode() {
+        let std_io = DoubleStdIo::new(vec!["3", "7"]);
+        let ui = Ui::new(std_io);
+        let mut board = Board::connect_four();
+        assert_eq!(Ok(Move::Place(38)), new_human(ui).get_move(&board));
+        board.set_mark(38, Mark::O);
+        let std_io = DoubleStdIo::new(vec!["3"]);
+        let ui = Ui::new(std_io);
+        assert_eq!(Ok(Move::Place(31)), new_human(ui).get_move(&board));
+    }
+
+    #[test]
+    fn it_does_not_prompt_when_the_game_is_over() {
+        let std_io = DoubleStdIo::new(vec![]);
+        let ui = Ui::new(std_io);
+        let board = board!["XXXOO    "];
+        assert_eq!(Err(MoveError::GameOver), new_human(ui).get_move(&board));
+    }
+
+    #[test]
+    fn it_shows_a_hint_without_playing_it() {
+        let std_io = DoubleStdIo::new(vec!["0", "hint"]);
+        let ui = Ui::new(std_io);
+        let mut human = Human::with_hint(Mark::X, ui, Negamax::default());
+        let board = board!["XX OO    "];
+        assert_eq!(Ok(Move::Place(0)), human.get_move(&board));
+        assert_eq!(
+            "Hint: play 2 (evaluation 998, wins in 1 ply)",
+            human.ui.io_mut().pop_output()
+        );
+    }
+
+    #[test]
+    fn it_shows_a_hint_as_a_column_in_gravity_mode() {
+        let std_io = DoubleStdIo::new(vec!["0", "hint"]);
+        let ui = Ui::new(std_io);
+        let mut human = Human::with_hint(Mark::X, ui, Negamax::default());
+        let mut board = Board::connect_four();
+        for &index in &[35, 36, 37] {
+            board.set_mark(index, Mark::X);
+        }
+        for &index in &[28, 29, 30] {
+            board.set_mark(index, Mark::O);
+        }
+        assert_eq!(Ok(Move::Place(21)), human.get_move(&board));
+        assert!(human.ui.io_mut().pop_output().starts_with("Hint: play 3 "));
+    }
+
+    #[test]
+    fn it_shows_a_hint_within_the_given_limits() {
+        let std_io = DoubleStdIo::new(vec!["4", "hint"]);
+        let ui = Ui::new(std_io);
+        let limits = SearchLimits {
+            max_depth: Some(1),
+            ..SearchLimits::default()
+        };
+        let mut human = Human::with_hint_limits(Mark::X, ui, Negamax::default(), limits);
+        assert_eq!(Ok(Move::Place(4)), human.get_move(&new_board()));
+        assert!(human
+            .ui
+            .io_mut()
+            .pop_output()
+            .ends_with(", outcome unknown)"));
+    }
+
+    #[test]
+    fn it_shows_the_exact_outcome_from_the_tablebase() {
+        let std_io = DoubleStdIo::new(vec!["0", "hint"]);
+        let ui = Ui::new(std_io);
+        let mut human = Human::with_hint(Mark::O, ui, Tablebase::default());
+        let board = board!["X X O   X"];
+        assert_eq!(Ok(Move::Place(0)), human.get_move(&board));
+        assert_eq!(
+            "Hint: play 1 (evaluation -997, loses in 2 plies)",
+            human.ui.io_mut().pop_output()
+        );
+    }
+
+    #[test]
+    fn it_shows_no_evaluation_when_the_ai_has_none() {
+        let std_io = DoubleStdIo::new(vec!["0", "hint"]);
+        let ui = Ui::new(std_io);
+        let mut human = Human::with_hint(Mark::X, ui, Mcts::default());
+        assert_eq!(Ok(Move::Place(0)), human.get_move(&new_board()));
+        let output = human.ui.io_mut().pop_output();
+        assert!(output.starts_with("Hint: play "));
+        assert!(!output.contains('('));
+    }
+
+    #[test]
+    fn it_says_when_no_hint_is_available() {
+        let std_io = DoubleStdIo::new(vec!["4", "hint"]);
+        let ui = Ui::new(std_io);
+        let mut human = new_human(ui);
+        assert_eq!(Ok(Move::Place(4)), human.get_move(&new_board()));
+        assert_eq!("No hint is available", human.ui.io_mut().pop_output());
     }
 
     fn new_human(ui: Ui<DoubleStdIo<'_>>) -> Human<DoubleStdIo<'_>> {
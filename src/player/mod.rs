@@ -6,8 +6,17 @@ pub use console::Human;
 
 use crate::Board;
 use crate::Mark;
+use crate::Move;
+use crate::MoveError;
+use crate::SearchStats;
 
 pub trait Player {
-    fn get_move(&self, board: &Board) -> usize;
+    fn get_move(&self, board: &Board) -> Result<Move, MoveError>;
     fn mark(&self) -> Mark;
+
+    fn stats(&self) -> Option<SearchStats> {
+        None
+    }
+
+    fn game_over(&self, _board: &Board, _winner: Option<Mark>) {}
 }
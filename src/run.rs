@@ -1,5 +1,218 @@
-use crate::Game;
+use crate::{Board, Difficulty, Game, Heuristic, Tuner, Ui, Variant};
+use std::env;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct Options {
+    pub variant: Variant,
+    pub difficulty: Difficulty,
+    pub seed: Option<u64>,
+    pub stats: bool,
+    pub tune: Option<PathBuf>,
+}
+
+impl Options {
+    const USAGE: &'static str =
+        "Usage: ttt-rs [--variant standard|achi|morris|disappearing|gravity] [--difficulty easy|medium|hard|perfect] [--seed N] [--stats] [--tune FILE]";
+
+    pub(crate) fn parse(args: &[String]) -> Result<Self, String> {
+        let mut options = Self::default();
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--variant" => {
+                    options.variant = iter
+                        .next()
+                        .and_then(|name| Self::parse_variant(name))
+                        .ok_or_else(|| Self::USAGE.to_owned())?;
+                }
+                "--difficulty" => {
+                    options.difficulty = iter
+                        .next()
+                        .and_then(|name| Self::parse_difficulty(name))
+                        .ok_or_else(|| Self::USAGE.to_owned())?;
+                }
+                "--seed" => {
+                    let seed = iter
+                        .next()
+                        .and_then(|value| value.parse::<u64>().ok())
+                        .ok_or_else(|| Self::USAGE.to_owned())?;
+                    options.seed = Some(seed);
+                }
+                "--stats" => options.stats = true,
+                "--tune" => {
+                    let path = iter.next().ok_or_else(|| Self::USAGE.to_owned())?;
+                    options.tune = Some(PathBuf::from(path));
+                }
+                _ => return Err(Self::USAGE.to_owned()),
+            }
+        }
+        Ok(options)
+    }
+
+    pub(crate) fn board(&self) -> Board {
+        match self.variant {
+            Variant::Gravity => Board::connect_four(),
+            Variant::Standard | Variant::Sliding { .. } | Variant::Disappearing { .. } => {
+                Board::with_variant(9, self.variant)
+            }
+        }
+    }
+
+    fn parse_variant(name: &str) -> Option<Variant> {
+        match name {
+            "standard" => Some(Variant::Standard),
+            "achi" => Some(Variant::Sliding { pieces: 4 }),
+            "morris" => Some(Variant::Sliding { pieces: 3 }),
+            "disappearing" => Some(Variant::Disappearing { marks: 3 }),
+            "gravity" => Some(Variant::Gravity),
+            _ => None,
+        }
+    }
+
+    fn parse_difficulty(name: &str) -> Option<Difficulty> {
+        match name {
+            "easy" => Some(Difficulty::Easy),
+            "medium" => Some(Difficulty::Medium),
+            "hard" => Some(Difficulty::Hard),
+            "perfect" => Some(Difficulty::Perfect),
+            _ => None,
+        }
+    }
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            variant: Variant::Standard,
+            difficulty: Difficulty::Perfect,
+            seed: None,
+            stats: false,
+            tune: None,
+        }
+    }
+}
 
 pub fn run() {
-    Game::run_with_defaults();
+    let args: Vec<String> = env::args().skip(1).collect();
+    match Options::parse(&args) {
+        Ok(options) => match &options.tune {
+            Some(path) => tune(&options, path),
+            None => Game::run_with_options(&options),
+        },
+        Err(usage) => Ui::with_defaults().print(&usage),
+    }
+}
+
+fn tune(options: &Options, path: &Path) {
+    let tuner = options.seed.map_or_else(Tuner::default, Tuner::with_seed);
+    let weights = tuner.tune(&options.board(), Heuristic::default());
+    let ui = Ui::with_defaults();
+    match weights.save(path) {
+        Ok(()) => ui.print(&format!("Wrote tuned weights to {}", path.display())),
+        Err(error) => ui.print(&format!("Could not write {}: {}", path.display(), error)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_defaults_to_the_standard_variant() {
+        assert_eq!(Ok(Options::default()), Options::parse(&[]));
+        assert_eq!(Variant::Standard, Options::default().variant);
+        assert_eq!(Difficulty::Perfect, Options::default().difficulty);
+    }
+
+    #[test]
+    fn it_parses_the_variant() {
+        let expectations = [
+            ("standard", Variant::Standard),
+            ("achi", Variant::Sliding { pieces: 4 }),
+            ("morris", Variant::Sliding { pieces: 3 }),
+            ("disappearing", Variant::Disappearing { marks: 3 }),
+            ("gravity", Variant::Gravity),
+        ];
+        for (name, variant) in &expectations {
+            let args = vec!["--variant".to_owned(), (*name).to_owned()];
+            let options = Options {
+                variant: *variant,
+                ..Options::default()
+            };
+            assert_eq!(Ok(options), Options::parse(&args));
+        }
+    }
+
+    #[test]
+    fn it_parses_the_difficulty() {
+        let expectations = [
+            ("easy", Difficulty::Easy),
+            ("medium", Difficulty::Medium),
+            ("hard", Difficulty::Hard),
+            ("perfect", Difficulty::Perfect),
+        ];
+        for (name, difficulty) in &expectations {
+            let args = vec![
+                "--variant".to_owned(),
+                "achi".to_owned(),
+                "--difficulty".to_owned(),
+                (*name).to_owned(),
+            ];
+            let options = Options {
+                variant: Variant::Sliding { pieces: 4 },
+                difficulty: *difficulty,
+                ..Options::default()
+            };
+            assert_eq!(Ok(options), Options::parse(&args));
+        }
+        let args = vec!["--difficulty".to_owned(), "bad".to_owned()];
+        assert_eq!(Err(Options::USAGE.to_owned()), Options::parse(&args));
+    }
+
+    #[test]
+    fn it_parses_the_seed() {
+        assert_eq!(None, Options::default().seed);
+        let args = vec!["--seed".to_owned(), "42".to_owned()];
+        let options = Options {
+            seed: Some(42),
+            ..Options::default()
+        };
+        assert_eq!(Ok(options), Options::parse(&args));
+        let args = vec!["--seed".to_owned(), "-1".to_owned()];
+        assert_eq!(Err(Options::USAGE.to_owned()), Options::parse(&args));
+    }
+
+    #[test]
+    fn it_parses_the_stats_flag() {
+        assert!(!Options::default().stats);
+        let options = Options {
+            stats: true,
+            ..Options::default()
+        };
+        assert_eq!(Ok(options), Options::parse(&["--stats".to_owned()]));
+    }
+
+    #[test]
+    fn it_parses_the_tuning_file() {
+        assert_eq!(None, Options::default().tune);
+        let args = vec!["--tune".to_owned(), "weights.txt".to_owned()];
+        let options = Options {
+            tune: Some(PathBuf::from("weights.txt")),
+            ..Options::default()
+        };
+        assert_eq!(Ok(options), Options::parse(&args));
+        let args = vec!["--tune".to_owned()];
+        assert_eq!(Err(Options::USAGE.to_owned()), Options::parse(&args));
+    }
+
+    #[test]
+    fn it_returns_usage_for_bad_arguments() {
+        let args = vec!["--variant".to_owned(), "bad".to_owned()];
+        assert_eq!(Err(Options::USAGE.to_owned()), Options::parse(&args));
+        let args = vec!["--variant".to_owned()];
+        assert_eq!(Err(Options::USAGE.to_owned()), Options::parse(&args));
+        let args = vec!["--bad".to_owned()];
+        assert_eq!(Err(Options::USAGE.to_owned()), Options::parse(&args));
+    }
 }
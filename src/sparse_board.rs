@@ -0,0 +1,306 @@
+use crate::Mark;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+pub type Coord = (i64, i64);
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Viewport {
+    pub min: Coord,
+    pub max: Coord,
+}
+
+#[derive(Clone, Debug)]
+pub struct SparseBoard {
+    cells: HashMap<Coord, Mark>,
+    win_length: usize,
+    history: Vec<(Coord, Mark)>,
+}
+
+impl SparseBoard {
+    const DIRECTIONS: [Coord; 4] = [(1, 0), (0, 1), (1, 1), (1, -1)];
+    const EMPTY_CELL: &'static str = ".";
+
+    #[allow(clippy::panic)]
+    pub fn new(win_length: usize) -> Self {
+        if win_length == 0 {
+            panic!("win length must be at least 1");
+        }
+        Self {
+            cells: HashMap::new(),
+            win_length,
+            history: vec![],
+        }
+    }
+
+    pub fn win_length(&self) -> usize {
+        self.win_length
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn mark(&self, coord: Coord) -> Option<&Mark> {
+        self.cells.get(&coord)
+    }
+
+    pub fn is_occupied(&self, coord: Coord) -> bool {
+        self.cells.contains_key(&coord)
+    }
+
+    pub fn last_move(&self) -> Option<(Coord, Mark)> {
+        self.history.last().copied()
+    }
+
+    pub fn apply_move(&mut self, coord: Coord, mark: Mark) -> bool {
+        if self.is_occupied(coord) {
+            return false;
+        }
+        self.cells.insert(coord, mark);
+        self.history.push((coord, mark));
+        true
+    }
+
+    pub fn undo_move(&mut self) -> Option<(Coord, Mark)> {
+        let (coord, mark) = self.history.pop()?;
+        self.cells.remove(&coord);
+        Some((coord, mark))
+    }
+
+    pub fn winner(&self) -> Option<Mark> {
+        let (coord, mark) = self.last_move()?;
+        Self::DIRECTIONS
+            .iter()
+            .any(|&direction| self.line_length(coord, mark, direction) >= self.win_length)
+            .then_some(mark)
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        self.winner().is_some()
+    }
+
+    pub fn bounds(&self) -> Option<Viewport> {
+        let mut coords = self.cells.keys();
+        let &first = coords.next()?;
+        Some(coords.fold(
+            Viewport {
+                min: first,
+                max: first,
+            },
+            |viewport, &(x, y)| Viewport {
+                min: (viewport.min.0.min(x), viewport.min.1.min(y)),
+                max: (viewport.max.0.max(x), viewport.max.1.max(y)),
+            },
+        ))
+    }
+
+    pub fn viewport(&self, margin: i64) -> Viewport {
+        let bounds = self.bounds().unwrap_or(Viewport {
+            min: (0, 0),
+            max: (0, 0),
+        });
+        Viewport {
+            min: (bounds.min.0 - margin, bounds.min.1 - margin),
+            max: (bounds.max.0 + margin, bounds.max.1 + margin),
+        }
+    }
+
+    pub fn candidate_moves(&self, radius: i64) -> Vec<Coord> {
+        if self.is_empty() {
+            return vec![(0, 0)];
+        }
+        let mut candidates: BTreeSet<(i64, i64)> = BTreeSet::new();
+        for &(x, y) in self.cells.keys() {
+            for dy in -radius..=radius {
+                for dx in -radius..=radius {
+                    let coord = (x + dx, y + dy);
+                    if !self.is_occupied(coord) {
+                        candidates.insert((coord.1, coord.0));
+                    }
+                }
+            }
+        }
+        candidates.into_iter().map(|(y, x)| (x, y)).collect()
+    }
+
+    pub fn render(&self, viewport: Viewport) -> String {
+        let label_width = [viewport.min, viewport.max]
+            .iter()
+            .flat_map(|&(x, y)| vec![x.to_string().len(), y.to_string().len()])
+            .max()
+            .unwrap_or(1);
+        let columns = (viewport.min.0..=viewport.max.0)
+            .map(|x| format!("{:>width$}", x, width = label_width))
+            .collect::<Vec<String>>();
+        let mut lines = vec![format!(
+            "{:>width$} {}",
+            "",
+            columns.join(" "),
+            width = label_width
+        )];
+        for y in viewport.min.1..=viewport.max.1 {
+            let row = (viewport.min.0..=viewport.max.0)
+                .map(|x| {
+                    let cell = self
+                        .mark((x, y))
+                        .map_or_else(|| Self::EMPTY_CELL.to_owned(), Mark::to_string);
+                    format!("{:>width$}", cell, width = label_width)
+                })
+                .collect::<Vec<String>>();
+            lines.push(format!(
+                "{:>width$} {}",
+                y,
+                row.join(" "),
+                width = label_width
+            ));
+        }
+        format!("{}\n", lines.join("\n"))
+    }
+
+    fn line_length(&self, (x, y): Coord, mark: Mark, (dx, dy): Coord) -> usize {
+        let mut length = 1;
+        for &sign in &[1, -1] {
+            let mut coord = (x + sign * dx, y + sign * dy);
+            while self.mark(coord) == Some(&mark) {
+                length += 1;
+                coord = (coord.0 + sign * dx, coord.1 + sign * dy);
+            }
+        }
+        length
+    }
+}
+
+impl fmt::Display for SparseBoard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.render(self.viewport(1)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_places_marks_anywhere() {
+        let mut board = SparseBoard::new(5);
+        assert!(board.is_empty());
+        assert!(board.apply_move((1_000_000, -1_000_000), Mark::X));
+        assert!(board.apply_move((-3, 7), Mark::O));
+        assert_eq!(Some(&Mark::X), board.mark((1_000_000, -1_000_000)));
+        assert_eq!(Some(&Mark::O), board.mark((-3, 7)));
+        assert_eq!(None, board.mark((0, 0)));
+        assert_eq!(2, board.len());
+    }
+
+    #[test]
+    fn it_rejects_occupied_cells() {
+        let mut board = SparseBoard::new(5);
+        assert!(board.apply_move((0, 0), Mark::X));
+        assert!(!board.apply_move((0, 0), Mark::O));
+        assert_eq!(Some(&Mark::X), board.mark((0, 0)));
+    }
+
+    #[test]
+    fn it_undoes_moves() {
+        let mut board = SparseBoard::new(5);
+        board.apply_move((2, 3), Mark::X);
+        assert_eq!(Some(((2, 3), Mark::X)), board.undo_move());
+        assert!(board.is_empty());
+        assert_eq!(None, board.undo_move());
+    }
+
+    #[test]
+    fn it_checks_for_winner_from_the_last_move() {
+        let mut board = SparseBoard::new(3);
+        board.apply_move((-1, -1), Mark::X);
+        board.apply_move((5, 5), Mark::O);
+        board.apply_move((1, 1), Mark::X);
+        assert_eq!(None, board.winner());
+        board.apply_move((5, 6), Mark::O);
+        board.apply_move((0, 0), Mark::X);
+        assert_eq!(Some(Mark::X), board.winner());
+        assert!(board.is_game_over());
+    }
+
+    #[test]
+    fn it_checks_for_winner_in_every_direction() {
+        let lines: [[Coord; 4]; 4] = [
+            [(0, 0), (1, 0), (2, 0), (3, 0)],
+            [(0, 0), (0, 1), (0, 2), (0, 3)],
+            [(0, 0), (1, 1), (2, 2), (3, 3)],
+            [(0, 0), (1, -1), (2, -2), (3, -3)],
+        ];
+        for line in &lines {
+            let mut board = SparseBoard::new(4);
+            for &coord in line {
+                assert_eq!(None, board.winner());
+                board.apply_move(coord, Mark::O);
+            }
+            assert_eq!(Some(Mark::O), board.winner());
+        }
+    }
+
+    #[test]
+    fn it_computes_bounds_and_viewport() {
+        let mut board = SparseBoard::new(5);
+        assert_eq!(None, board.bounds());
+        board.apply_move((-2, 4), Mark::X);
+        board.apply_move((3, -1), Mark::O);
+        let bounds = Viewport {
+            min: (-2, -1),
+            max: (3, 4),
+        };
+        assert_eq!(Some(bounds), board.bounds());
+        let viewport = Viewport {
+            min: (-3, -2),
+            max: (4, 5),
+        };
+        assert_eq!(viewport, board.viewport(1));
+    }
+
+    #[test]
+    fn it_generates_candidates_near_stones() {
+        let mut board = SparseBoard::new(5);
+        assert_eq!(vec![(0, 0)], board.candidate_moves(1));
+        board.apply_move((0, 0), Mark::X);
+        let expected = vec![
+            (-1, -1),
+            (0, -1),
+            (1, -1),
+            (-1, 0),
+            (1, 0),
+            (-1, 1),
+            (0, 1),
+            (1, 1),
+        ];
+        assert_eq!(expected, board.candidate_moves(1));
+        board.apply_move((10, 10), Mark::O);
+        assert_eq!(16, board.candidate_moves(1).len());
+        assert_eq!(48, board.candidate_moves(2).len());
+    }
+
+    #[test]
+    fn it_renders_a_viewport() {
+        let mut board = SparseBoard::new(5);
+        board.apply_move((0, 0), Mark::X);
+        board.apply_move((1, 1), Mark::O);
+        let board_string = r#"   -1  0  1  2
+-1  .  .  .  .
+ 0  .  X  .  .
+ 1  .  .  O  .
+ 2  .  .  .  .
+"#;
+
+        assert_eq!(board_string, format!("{}", board));
+        let viewport = Viewport {
+            min: (0, 0),
+            max: (1, 0),
+        };
+        assert_eq!("  0 1\n0 X .\n", board.render(viewport));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{ConsoleIo, StdIo};
+use crate::{ConsoleIo, Outcome, SearchStats, StdIo};
 
 #[derive(Debug)]
 pub struct Ui<T: StdIo> {
@@ -9,7 +9,13 @@ impl<T> Ui<T>
 where
     T: StdIo,
 {
+    const COLUMN_MESSAGE: &'static str = "Drop a mark into a column";
+    const DISAPPEAR_MESSAGE: &'static str = "Your oldest mark disappears from";
     const DRAW_MESSAGE: &'static str = "It is a draw game";
+    const HINT_MESSAGE: &'static str = "Hint: play";
+    const NO_HINT_MESSAGE: &'static str = "No hint is available";
+    const STATS_MESSAGE: &'static str = "Searched";
+    const SLIDE_MESSAGE: &'static str = "Slide a mark to an adjacent cell (from-to)";
     const TURN_MESSAGE: &'static str = "Make your move";
     const WIN_MESSAGE: &'static str = "The winner is";
 
@@ -26,6 +32,19 @@ where
             .println(&format!("{}, {}", Self::TURN_MESSAGE, mark_string));
     }
 
+    pub fn print_slide_message(&self) {
+        self.io.println(Self::SLIDE_MESSAGE);
+    }
+
+    pub fn print_column_message(&self) {
+        self.io.println(Self::COLUMN_MESSAGE);
+    }
+
+    pub fn print_disappear_message(&self, cell_string: &str) {
+        self.io
+            .println(&format!("{} {}", Self::DISAPPEAR_MESSAGE, cell_string));
+    }
+
     pub fn print_winner(&self, mark_string: &str) {
         self.io
             .println(&format!("{} {}", Self::WIN_MESSAGE, mark_string));
@@ -35,6 +54,48 @@ where
         self.io.println(Self::DRAW_MESSAGE);
     }
 
+    pub fn print_stats(&self, stats: &SearchStats) {
+        self.io.println(&format!(
+            "{} {} nodes in {} ms ({} leaf evaluations, {} beta cutoffs, {} table hits)",
+            Self::STATS_MESSAGE,
+            stats.nodes,
+            stats.elapsed.as_millis(),
+            stats.leaf_evaluations,
+            stats.beta_cutoffs,
+            stats.table_hits
+        ));
+    }
+
+    pub fn print_hint(&self, cell_string: &str, score: Option<i16>, outcome: Outcome) {
+        let plies_string = |plies: usize| match plies {
+            1 => "1 ply".to_owned(),
+            _ => format!("{} plies", plies),
+        };
+        let outcome_string = match outcome {
+            Outcome::Win { plies } => format!("wins in {}", plies_string(plies)),
+            Outcome::Loss { plies } => format!("loses in {}", plies_string(plies)),
+            Outcome::Draw => "draws".to_owned(),
+            Outcome::Unknown => "outcome unknown".to_owned(),
+        };
+        let details = match (score, outcome) {
+            (Some(evaluation), _) => format!(" (evaluation {}, {})", evaluation, outcome_string),
+            (None, Outcome::Unknown) => String::new(),
+            (None, Outcome::Win { .. } | Outcome::Draw | Outcome::Loss { .. }) => {
+                format!(" ({})", outcome_string)
+            }
+        };
+        self.io.println(&format!(
+            "{} {}{}",
+            Self::HINT_MESSAGE,
+            cell_string,
+            details
+        ));
+    }
+
+    pub fn print_no_hint(&self) {
+        self.io.println(Self::NO_HINT_MESSAGE);
+    }
+
     pub fn print(&self, text: &str) {
         self.io.println(text);
     }
@@ -56,6 +117,7 @@ impl Ui<ConsoleIo> {
 mod tests {
     use super::*;
     use crate::test_common::DoubleStdIo;
+    use std::time::Duration;
 
     #[test]
     fn it_prompts() {
@@ -73,6 +135,33 @@ mod tests {
         assert_eq!(text, ui.io_mut().pop_output());
     }
 
+    #[test]
+    fn it_prints_slide_message() {
+        let std_io = DoubleStdIo::new(vec![]);
+        let mut ui = Ui::new(std_io);
+        let text = "Slide a mark to an adjacent cell (from-to)";
+        ui.print_slide_message();
+        assert_eq!(text, ui.io_mut().pop_output());
+    }
+
+    #[test]
+    fn it_prints_column_message() {
+        let std_io = DoubleStdIo::new(vec![]);
+        let mut ui = Ui::new(std_io);
+        let text = "Drop a mark into a column";
+        ui.print_column_message();
+        assert_eq!(text, ui.io_mut().pop_output());
+    }
+
+    #[test]
+    fn it_prints_disappear_message() {
+        let std_io = DoubleStdIo::new(vec![]);
+        let mut ui = Ui::new(std_io);
+        let text = "Your oldest mark disappears from 3";
+        ui.print_disappear_message("3");
+        assert_eq!(text, ui.io_mut().pop_output());
+    }
+
     #[test]
     fn it_prints_winner() {
         let std_io = DoubleStdIo::new(vec![]);
@@ -91,6 +180,62 @@ mod tests {
         assert_eq!(text, ui.io_mut().pop_output());
     }
 
+    #[test]
+    fn it_prints_hint() {
+        let std_io = DoubleStdIo::new(vec![]);
+        let mut ui = Ui::new(std_io);
+        ui.print_hint("4", Some(995), Outcome::Win { plies: 3 });
+        assert_eq!(
+            "Hint: play 4 (evaluation 995, wins in 3 plies)",
+            ui.io_mut().pop_output()
+        );
+        ui.print_hint("8", Some(-997), Outcome::Loss { plies: 1 });
+        assert_eq!(
+            "Hint: play 8 (evaluation -997, loses in 1 ply)",
+            ui.io_mut().pop_output()
+        );
+        ui.print_hint("2-5", Some(0), Outcome::Draw);
+        assert_eq!(
+            "Hint: play 2-5 (evaluation 0, draws)",
+            ui.io_mut().pop_output()
+        );
+        ui.print_hint("0", Some(0), Outcome::Unknown);
+        assert_eq!(
+            "Hint: play 0 (evaluation 0, outcome unknown)",
+            ui.io_mut().pop_output()
+        );
+        ui.print_hint("6", None, Outcome::Draw);
+        assert_eq!("Hint: play 6 (draws)", ui.io_mut().pop_output());
+        ui.print_hint("1", None, Outcome::Unknown);
+        assert_eq!("Hint: play 1", ui.io_mut().pop_output());
+    }
+
+    #[test]
+    fn it_prints_no_hint() {
+        let std_io = DoubleStdIo::new(vec![]);
+        let mut ui = Ui::new(std_io);
+        ui.print_no_hint();
+        assert_eq!("No hint is available", ui.io_mut().pop_output());
+    }
+
+    #[test]
+    fn it_prints_search_stats() {
+        let std_io = DoubleStdIo::new(vec![]);
+        let mut ui = Ui::new(std_io);
+        let stats = SearchStats {
+            nodes: 120,
+            leaf_evaluations: 80,
+            beta_cutoffs: 15,
+            table_hits: 7,
+            elapsed: Duration::from_millis(42),
+            root_moves: vec![],
+        };
+        let text =
+            "Searched 120 nodes in 42 ms (80 leaf evaluations, 15 beta cutoffs, 7 table hits)";
+        ui.print_stats(&stats);
+        assert_eq!(text, ui.io_mut().pop_output());
+    }
+
     #[test]
     fn it_prints_custom_text() {
         let std_io = DoubleStdIo::new(vec![]);
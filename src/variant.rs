@@ -0,0 +1,13 @@
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Variant {
+    Standard,
+    Sliding { pieces: usize },
+    Disappearing { marks: usize },
+    Gravity,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Phase {
+    Placement,
+    Movement,
+}
@@ -1,5 +1,8 @@
 use std::cell::RefCell;
-use ttt_rs::{Board, Game, Human, Mark, Player, StdIo, Ui};
+use std::rc::Rc;
+use ttt_rs::{
+    Board, Computer, Game, Human, Mark, Menace, Move, MoveError, Player, StdIo, Ui, Variant,
+};
 
 struct DoubleStdIo<'a> {
     inputs: RefCell<Vec<&'a str>>,
@@ -85,3 +88,120 @@ fn player_x_win() -> Vec<&'static str> {
 fn player_o_lose() -> Vec<&'static str> {
     vec!["5", "3", "1"]
 }
+
+#[test]
+fn it_runs_through_a_sliding_game() {
+    let board = Board::with_variant(9, Variant::Sliding { pieces: 3 });
+    let x_std_io = DoubleStdIo::new(vec!["6-7", "6", "1", "0"]);
+    let x_ui = Ui::new(x_std_io);
+    let o_std_io = DoubleStdIo::new(vec!["3-6", "3", "2", "4"]);
+    let o_ui = Ui::new(o_std_io);
+    let players: Vec<Box<dyn Player>> = vec![
+        Box::new(Human::new(Mark::X, x_ui)),
+        Box::new(Human::new(Mark::O, o_ui)),
+    ];
+    let mut game = Game::new(board, players, Ui::new(DoubleStdIo::new(vec![])));
+    game.run();
+
+    assert_eq!(&Mark::O, game.board().winner().unwrap());
+    let io_mut = game.ui_mut().io_mut();
+    assert!(io_mut.does_contain("Slide"));
+    assert!(io_mut.does_contain("winner"));
+}
+
+#[test]
+fn it_finishes_a_game_that_is_already_over() {
+    let mut board = Board::new(9);
+    for index in 0..3 {
+        board.set_mark(index, Mark::X);
+    }
+    board.set_mark(3, Mark::O);
+    board.set_mark(4, Mark::O);
+    let players: Vec<Box<dyn Player>> = vec![
+        Box::new(Computer::with_defaults(Mark::O)),
+        Box::new(Computer::with_defaults(Mark::X)),
+    ];
+    let mut game = Game::new(board, players, Ui::new(DoubleStdIo::new(vec![])));
+    game.run();
+
+    let io_mut = game.ui_mut().io_mut();
+    assert!(io_mut.does_contain("winner"));
+    assert!(!io_mut.does_contain("Make your move"));
+}
+
+#[test]
+fn it_ends_the_game_when_a_player_cannot_move() {
+    let mut board = Board::with_variant(9, Variant::Sliding { pieces: 4 });
+    for &index in &[1, 2, 3, 6] {
+        board.set_mark(index, Mark::X);
+    }
+    for &index in &[0, 4, 5, 7] {
+        board.set_mark(index, Mark::O);
+    }
+    let players: Vec<Box<dyn Player>> = vec![
+        Box::new(Computer::with_defaults(Mark::X)),
+        Box::new(Computer::with_defaults(Mark::O)),
+    ];
+    let mut game = Game::new(board, players, Ui::new(DoubleStdIo::new(vec![])));
+    game.run();
+
+    let io_mut = game.ui_mut().io_mut();
+    assert!(io_mut.does_contain(&format!("winner is {}", Mark::O)));
+}
+
+#[test]
+fn it_prints_search_stats_after_computer_moves() {
+    let mut board = Board::new(9);
+    board.set_mark(0, Mark::X);
+    board.set_mark(1, Mark::X);
+    board.set_mark(4, Mark::O);
+    let players: Vec<Box<dyn Player>> = vec![
+        Box::new(Computer::with_defaults(Mark::O)),
+        Box::new(Computer::with_defaults(Mark::X)),
+    ];
+    let ui = Ui::new(DoubleStdIo::new(vec![]));
+    let mut game = Game::with_stats(board, players, ui, true);
+    game.run();
+
+    assert!(game.ui_mut().io_mut().does_contain("nodes in"));
+}
+
+struct RecordingPlayer {
+    inner: Computer<Menace>,
+    results: Rc<RefCell<Vec<Option<Mark>>>>,
+}
+
+impl Player for RecordingPlayer {
+    fn get_move(&self, board: &Board) -> Result<Move, MoveError> {
+        self.inner.get_move(board)
+    }
+
+    fn mark(&self) -> Mark {
+        self.inner.mark()
+    }
+
+    fn game_over(&self, board: &Board, winner: Option<Mark>) {
+        self.inner.game_over(board, winner);
+        self.results.borrow_mut().push(winner);
+    }
+}
+
+#[test]
+fn it_tells_every_player_when_the_game_is_over() {
+    let results = Rc::new(RefCell::new(vec![]));
+    let players: Vec<Box<dyn Player>> = vec![
+        Box::new(RecordingPlayer {
+            inner: Computer::new(Menace::with_seed(1), Mark::X),
+            results: Rc::clone(&results),
+        }),
+        Box::new(RecordingPlayer {
+            inner: Computer::new(Menace::with_seed(2), Mark::O),
+            results: Rc::clone(&results),
+        }),
+    ];
+    let mut game = Game::new(Board::new(9), players, Ui::new(DoubleStdIo::new(vec![])));
+    game.run();
+
+    let winner = game.board().winner().copied();
+    assert_eq!(vec![winner, winner], *results.borrow());
+}